@@ -1,14 +1,19 @@
-use std::{collections::HashSet, num::ParseIntError};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    io::BufRead,
+    num::ParseIntError,
+};
 
 use aoc_lib::{Bench, BenchResult, Day, NoError, ParseResult, UserError};
-use color_eyre::{Report, Result};
+use color_eyre::{eyre::eyre, Report, Result};
 
 pub const DAY: Day = Day {
     day: 1,
     name: "Chronal Calibration",
     part_1: run_part1,
     part_2: Some(run_part2),
-    other: &[("Parse", run_parse)],
+    other: &[("Parse", run_parse), ("Drift", run_drift)],
 };
 
 fn run_part1(input: &str, b: Bench) -> BenchResult {
@@ -18,7 +23,8 @@ fn run_part1(input: &str, b: Bench) -> BenchResult {
 
 fn run_part2(input: &str, b: Bench) -> BenchResult {
     let data = parse(input).map_err(UserError)?;
-    b.bench(|| Ok::<_, NoError>(part2(&data)))
+    part2(&data).map_err(UserError)?;
+    b.bench(|| part2(&data))
 }
 
 fn run_parse(input: &str, b: Bench) -> BenchResult {
@@ -28,26 +34,159 @@ fn run_parse(input: &str, b: Bench) -> BenchResult {
     })
 }
 
+fn run_drift(input: &str, b: Bench) -> BenchResult {
+    b.bench(|| analyse_drift(input.as_bytes()))
+}
+
+/// Splits a line into its changes, which may be comma-separated as in the puzzle text.
+fn changes(line: &str) -> impl Iterator<Item = &str> {
+    line.split(',').map(str::trim).filter(|c| !c.is_empty())
+}
+
 fn parse(input: &str) -> Result<Vec<i32>, ParseIntError> {
-    input.lines().map(str::trim).map(str::parse).collect()
+    input.lines().flat_map(changes).map(str::parse).collect()
+}
+
+fn part1(values: &[i32]) -> i64 {
+    values.iter().map(|&v| v as i64).sum()
+}
+
+/// Summary of a single pass over a list of changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Drift {
+    changes: usize,
+    frequency: i64,
+    min: i64,
+    max: i64,
+    /// Index of the first change that lands on a frequency already seen during the pass.
+    first_repeat: Option<usize>,
 }
 
-fn part1(values: &[i32]) -> i32 {
-    values.iter().sum()
+impl Display for Drift {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} after {} changes, range {}..={}",
+            self.frequency, self.changes, self.min, self.max
+        )?;
+
+        match self.first_repeat {
+            Some(idx) => write!(f, ", first repeat at change {idx}"),
+            None => write!(f, ", no repeat"),
+        }
+    }
 }
 
-fn part2(values: &[i32]) -> i32 {
-    let mut seen = HashSet::new();
+/// Reads changes from `reader` one line at a time, so the whole list never needs to be in memory.
+/// Only the frequencies seen before the first repeat are kept.
+fn analyse_drift(mut reader: impl BufRead) -> Result<Drift> {
+    let mut drift = Drift {
+        changes: 0,
+        frequency: 0,
+        min: 0,
+        max: 0,
+        first_repeat: None,
+    };
+    let mut seen = HashSet::from([0]);
+    let mut line = String::new();
+    let mut line_number = 0;
+
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            break;
+        }
+        line_number += 1;
+
+        for change in changes(&line) {
+            let change: i64 = change
+                .parse()
+                .map_err(|e| eyre!("line {line_number}: invalid change `{change}`: {e}"))?;
+
+            drift.frequency = drift.frequency.checked_add(change).ok_or_else(|| {
+                eyre!(
+                    "frequency overflowed at change {} on line {line_number}",
+                    drift.changes
+                )
+            })?;
+            drift.min = drift.min.min(drift.frequency);
+            drift.max = drift.max.max(drift.frequency);
+
+            if drift.first_repeat.is_none() && !seen.insert(drift.frequency) {
+                drift.first_repeat = Some(drift.changes);
+                seen = HashSet::new();
+            }
+            drift.changes += 1;
+        }
+    }
 
-    values
-        .iter()
-        .cycle()
-        .scan(0, |state, &change| {
-            *state += change;
-            Some(*state)
+    Ok(drift)
+}
+
+/// Finds the first frequency reached twice without simulating the repeated passes.
+///
+/// Every frequency reached is some first-pass frequency `f[i]` shifted by a whole number of
+/// passes' drift, `f[i] + m * drift`, at time `m * len + i`. If the first pass has no repeat
+/// itself, a later repeat means `f[j] + m * drift == f[i]` for some `m > 0`. That requires
+/// `f[i]` and `f[j]` to be congruent modulo the drift, and for a given `j` the nearest such
+/// `f[i]` in the direction of the drift gives the earliest repeat, so we only need to compare
+/// neighbours after grouping by residue and sorting.
+fn part2(values: &[i32]) -> Result<i64> {
+    if values.is_empty() {
+        return Err(eyre!("no frequency changes given"));
+    }
+
+    // The frequencies at the start of each step of the first pass, including the initial 0.
+    let mut frequencies = Vec::with_capacity(values.len());
+    let mut seen = HashSet::with_capacity(values.len());
+    let mut frequency = 0_i64;
+    for &change in values {
+        if !seen.insert(frequency) {
+            return Ok(frequency);
+        }
+        frequencies.push(frequency);
+        frequency += change as i64;
+    }
+
+    let drift = frequency;
+    if drift == 0 {
+        // The second pass starts back at 0.
+        return Ok(0);
+    }
+
+    let mut groups: HashMap<i64, Vec<(i64, usize)>> = HashMap::new();
+    for (idx, &f) in frequencies.iter().enumerate() {
+        groups
+            .entry(f.rem_euclid(drift.abs()))
+            .or_default()
+            .push((f, idx));
+    }
+
+    let len = values.len() as i64;
+    groups.values_mut().for_each(|group| group.sort_unstable());
+
+    let first_repeat = groups
+        .values()
+        .flat_map(|group| group.windows(2))
+        .map(|pair| {
+            let [(lower, lower_idx), (higher, higher_idx)] = *pair else {
+                unreachable!()
+            };
+
+            // The frequency that drifts onto the other one, and the one it lands on.
+            let (start_idx, target) = if drift > 0 {
+                (lower_idx, higher)
+            } else {
+                (higher_idx, lower)
+            };
+            let passes = (higher - lower) / drift.abs();
+            (passes * len + start_idx as i64, target)
         })
-        .find(|&f| !seen.insert(f))
-        .unwrap()
+        .min();
+
+    first_repeat
+        .map(|(_, frequency)| frequency)
+        .ok_or_else(|| eyre!("no frequency is ever reached twice"))
 }
 
 #[cfg(test)]
@@ -55,6 +194,8 @@ mod tests {
     use super::*;
     use aoc_lib::Example;
 
+    use crate::days::rng::Rng;
+
     #[test]
     fn part1_test() {
         let data = aoc_lib::input(DAY.day)
@@ -64,4 +205,110 @@ mod tests {
 
         assert_eq!(data.len(), 0);
     }
+
+    #[test]
+    fn parse_test() {
+        let expected = [1, -2, 3, 1];
+
+        assert_eq!(expected, *parse("+1, -2, +3, +1").unwrap());
+        assert_eq!(expected, *parse("+1\n-2\n+3\n+1\n").unwrap());
+        assert_eq!(expected, *parse("+1, -2\n+3,\n+1").unwrap());
+    }
+
+    #[test]
+    fn drift_test() {
+        let expected = Drift {
+            changes: 4,
+            frequency: 3,
+            min: -1,
+            max: 3,
+            first_repeat: None,
+        };
+        let actual = analyse_drift("+1, -2, +3, +1".as_bytes()).unwrap();
+        assert_eq!(expected, actual);
+
+        let expected = Drift {
+            changes: 4,
+            frequency: 0,
+            min: -1,
+            max: 2,
+            first_repeat: Some(3),
+        };
+        let actual = analyse_drift("+1\n-2, +3\n-2\n".as_bytes()).unwrap();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn drift_overflow_test() {
+        let input = format!("+{}\n-1\n+1\n+1", i64::MAX);
+        let err = analyse_drift(input.as_bytes()).unwrap_err().to_string();
+
+        assert!(err.contains("change 3 on line 4"), "{err}");
+    }
+
+    /// The straightforward simulation, which only terminates if there is a repeat.
+    fn simulate_part2(values: &[i32]) -> i64 {
+        let mut seen = HashSet::from([0]);
+
+        values
+            .iter()
+            .cycle()
+            .scan(0, |state, &change| {
+                *state += change as i64;
+                Some(*state)
+            })
+            .find(|&f| !seen.insert(f))
+            .unwrap()
+    }
+
+    #[test]
+    fn part2_test() {
+        let tests: [(&[i32], i64); 4] = [
+            (&[1, -1], 0),
+            (&[3, 3, 4, -2, -4], 10),
+            (&[-6, 3, 8, 5, -6], 5),
+            (&[7, 7, -2, -7, -4], 14),
+        ];
+
+        for (input, expected) in tests {
+            assert_eq!(expected, part2(input).unwrap(), "{input:?}");
+            assert_eq!(expected, simulate_part2(input), "{input:?}");
+        }
+    }
+
+    #[test]
+    fn part2_wide_test() {
+        // Both of these overflow an i32 frequency, and the second drifts by exactly i32::MIN.
+        let max = i32::MAX;
+        let tests: [(&[i32], i64); 2] = [
+            (&[max, max, -max, -max], max as i64),
+            (&[i32::MIN, 0], i32::MIN as i64),
+        ];
+
+        for (input, expected) in tests {
+            assert_eq!(expected, part2(input).unwrap(), "{input:?}");
+            assert_eq!(expected, simulate_part2(input), "{input:?}");
+        }
+    }
+
+    #[test]
+    fn part2_no_repeat_test() {
+        assert!(part2(&[1, 1]).is_err());
+        assert!(part2(&[]).is_err());
+    }
+
+    #[test]
+    fn part2_simulation_test() {
+        let mut rng = Rng::new();
+        let mut next = |modulus: u64| rng.below(modulus) as i32;
+
+        for _ in 0..200 {
+            let len = next(12) as usize + 1;
+            let values: Vec<_> = (0..len).map(|_| next(41) - 20).collect();
+
+            if let Ok(actual) = part2(&values) {
+                assert_eq!(simulate_part2(&values), actual, "{values:?}");
+            }
+        }
+    }
 }
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use aoc_lib::{Bench, BenchResult, Day, NoError, ParseResult, UserError};
 use color_eyre::{eyre::eyre, Report, Result};
 use itertools::Itertools;
@@ -7,7 +9,12 @@ pub const DAY: Day = Day {
     name: "Inventory Management System",
     part_1: run_part1,
     part_2: Some(run_part2),
-    other: &[("Parse", run_parse)],
+    other: &[
+        ("Parse", run_parse),
+        ("Case Folded Checksum", run_folded_checksum),
+        ("Hamming Clusters", run_hamming_clusters),
+        ("Levenshtein Clusters", run_levenshtein_clusters),
+    ],
 };
 
 fn run_part1(input: &str, b: Bench) -> BenchResult {
@@ -17,7 +24,7 @@ fn run_part1(input: &str, b: Bench) -> BenchResult {
 
 fn run_part2(input: &str, b: Bench) -> BenchResult {
     let data = parse(input).map_err(UserError)?;
-    b.bench(|| Ok::<_, NoError>(part2(&data)))
+    b.bench(|| part2(&data))
 }
 
 fn run_parse(input: &str, b: Bench) -> BenchResult {
@@ -27,11 +34,26 @@ fn run_parse(input: &str, b: Bench) -> BenchResult {
     })
 }
 
+fn run_folded_checksum(input: &str, b: Bench) -> BenchResult {
+    let data = parse(input).map_err(UserError)?;
+    b.bench(|| Ok::<_, NoError>(checksum(&data, CasePolicy::Fold)))
+}
+
+fn run_hamming_clusters(input: &str, b: Bench) -> BenchResult {
+    let data = parse(input).map_err(UserError)?;
+    b.bench(|| Ok::<_, NoError>(near_duplicate_groups(&data, Metric::Hamming)))
+}
+
+fn run_levenshtein_clusters(input: &str, b: Bench) -> BenchResult {
+    let data = parse(input).map_err(UserError)?;
+    b.bench(|| Ok::<_, NoError>(near_duplicate_groups(&data, Metric::Levenshtein)))
+}
+
 fn parse(input: &str) -> Result<Vec<&str>> {
     let mut ids = Vec::new();
 
     for line in input.lines().map(str::trim) {
-        if line.bytes().any(|b| !b.is_ascii_alphabetic()) {
+        if line.chars().any(|c| !c.is_alphabetic()) {
             return Err(eyre!("Invalid character in ID: `{line}`"));
         }
 
@@ -41,48 +63,342 @@ fn parse(input: &str) -> Result<Vec<&str>> {
     Ok(ids)
 }
 
-fn part1(ids: &[&str]) -> u16 {
-    let mut num_threes = 0;
-    let mut num_twos = 0;
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CasePolicy {
+    /// Upper and lower case forms count as the same letter.
+    Fold,
+    /// Every distinct character counts separately.
+    Distinct,
+}
+
+/// Counts how many IDs contain some letter exactly `k` times, for each `k` in `multiplicities`.
+fn count_multiplicities<const N: usize>(
+    ids: &[&str],
+    multiplicities: [usize; N],
+    case: CasePolicy,
+) -> [usize; N] {
+    let mut totals = [0; N];
+    let mut letters = Vec::new();
 
     for id in ids {
-        let counts = id.bytes().fold([0u8; 26], |mut acc, b| {
-            acc[(b - b'a') as usize] += 1;
-            acc
-        });
+        letters.clear();
+        match case {
+            CasePolicy::Fold => letters.extend(id.chars().flat_map(char::to_lowercase)),
+            CasePolicy::Distinct => letters.extend(id.chars()),
+        }
+        letters.sort_unstable();
 
-        let (has_two, has_three) = counts
-            .into_iter()
-            .fold((false, false), |(has_two, has_three), count| {
-                (has_two || count == 2, has_three || count == 3)
-            });
+        let mut found = [false; N];
+        for (count, _) in letters.iter().dedup_with_count() {
+            for (found, &k) in found.iter_mut().zip(&multiplicities) {
+                *found |= count == k;
+            }
+        }
 
-        num_threes += has_three as u16;
-        num_twos += has_two as u16;
+        for (total, found) in totals.iter_mut().zip(found) {
+            *total += found as usize;
+        }
     }
 
-    num_threes * num_twos
+    totals
 }
 
-fn part2(ids: &[&str]) -> String {
-    let Some((a, b)) = ids.iter()
-        .cartesian_product(ids)
-        .find(|(a, b)| a.bytes().zip(b.bytes()).filter(|(a, b)| a != b).count() == 1) else {
-            panic!("Matched pair not found");
+fn checksum(ids: &[&str], case: CasePolicy) -> usize {
+    let [twos, threes] = count_multiplicities(ids, [2, 3], case);
+    twos * threes
+}
+
+fn part1(ids: &[&str]) -> usize {
+    checksum(ids, CasePolicy::Distinct)
+}
+
+/// Two IDs that are the same except at one position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct NearMatch {
+    a: usize,
+    b: usize,
+    position: usize,
+}
+
+/// Finds every pair of IDs that differ in exactly one position, as indices into `ids`.
+///
+/// For each position we bucket the IDs by a hash of everything but that position, so only IDs
+/// that might match are ever compared. The hashes are polynomial, so masking a position out is
+/// a single subtraction and the whole search is O(n·L) plus the size of the output.
+fn near_matches(ids: &[&str]) -> Vec<NearMatch> {
+    const BASE: u64 = 0x100000001b3;
+
+    let ids: Vec<Vec<char>> = ids.iter().map(|id| id.chars().collect()).collect();
+    let max_len = ids.iter().map(Vec::len).max().unwrap_or(0);
+    let powers: Vec<u64> = std::iter::successors(Some(1u64), |p| Some(p.wrapping_mul(BASE)))
+        .take(max_len)
+        .collect();
+    let hashes: Vec<u64> = ids
+        .iter()
+        .map(|id| {
+            id.iter()
+                .fold(0u64, |h, &c| h.wrapping_mul(BASE).wrapping_add(c as u64))
+        })
+        .collect();
+
+    let mut matches = Vec::new();
+    let mut buckets: HashMap<(usize, u64), Vec<usize>> = HashMap::new();
+
+    for position in 0..max_len {
+        buckets.clear();
+
+        for (idx, (id, &hash)) in ids.iter().zip(&hashes).enumerate() {
+            let Some(&masked) = id.get(position) else {
+                continue;
+            };
+
+            let weight = powers[id.len() - 1 - position];
+            let masked_hash = hash.wrapping_sub((masked as u64).wrapping_mul(weight));
+            buckets
+                .entry((id.len(), masked_hash))
+                .or_default()
+                .push(idx);
+        }
+
+        for bucket in buckets.values_mut().filter(|b| b.len() > 1) {
+            // Identical IDs share every bucket, so group the bucket by the character at this
+            // position and only pair up IDs from different groups.
+            let char_at = |idx: usize| ids[idx][position];
+            bucket.sort_by_key(|&idx| (char_at(idx), idx));
+
+            let mut start = 0;
+            while start < bucket.len() {
+                let c = char_at(bucket[start]);
+                let end = start
+                    + bucket[start..]
+                        .iter()
+                        .take_while(|&&idx| char_at(idx) == c)
+                        .count();
+
+                for &a in &bucket[start..end] {
+                    for &b in &bucket[end..] {
+                        let (a_id, b_id) = (&ids[a], &ids[b]);
+                        // Guard against hash collisions.
+                        if a_id[..position] == b_id[..position]
+                            && a_id[position + 1..] == b_id[position + 1..]
+                        {
+                            matches.push(NearMatch {
+                                a: a.min(b),
+                                b: a.max(b),
+                                position,
+                            });
+                        }
+                    }
+                }
+
+                start = end;
+            }
+        }
+    }
+
+    matches.sort_unstable();
+    matches
+}
+
+fn part2(ids: &[&str]) -> Result<String> {
+    let NearMatch { a, position, .. } = near_matches(ids)
+        .into_iter()
+        .next()
+        .ok_or_else(|| eyre!("No pair of IDs differs by exactly one character"))?;
+
+    Ok(ids[a]
+        .chars()
+        .enumerate()
+        .filter(|&(i, _)| i != position)
+        .map(|(_, c)| c)
+        .collect())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Metric {
+    /// Number of positions that differ, with the shorter ID treated as padded by a character
+    /// that matches nothing, so IDs of different lengths are still comparable.
+    Hamming,
+    /// Fewest single character insertions, deletions or substitutions.
+    Levenshtein,
+}
+
+impl Metric {
+    fn distance(self, a: &str, b: &str) -> usize {
+        match self {
+            Metric::Hamming => {
+                let (a_len, b_len) = (a.chars().count(), b.chars().count());
+                let mismatches = a.chars().zip(b.chars()).filter(|(a, b)| a != b).count();
+                mismatches + a_len.abs_diff(b_len)
+            }
+            Metric::Levenshtein => {
+                let b: Vec<char> = b.chars().collect();
+                let mut prev: Vec<usize> = (0..=b.len()).collect();
+                let mut cur = vec![0; b.len() + 1];
+
+                for (i, a) in a.chars().enumerate() {
+                    cur[0] = i + 1;
+                    for (j, &b) in b.iter().enumerate() {
+                        let substitute = prev[j] + (a != b) as usize;
+                        cur[j + 1] = substitute.min(prev[j + 1] + 1).min(cur[j] + 1);
+                    }
+                    std::mem::swap(&mut prev, &mut cur);
+                }
+
+                prev[b.len()]
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct BkNode {
+    id: usize,
+    /// Child nodes, keyed by their distance from this node.
+    children: Vec<(usize, usize)>,
+}
+
+/// A metric tree over a list of IDs. Each child of a node is at a known distance from it, so the
+/// triangle inequality lets a query skip any subtree that can't be close enough.
+#[derive(Debug, Clone)]
+struct BkTree<'a> {
+    ids: &'a [&'a str],
+    metric: Metric,
+    nodes: Vec<BkNode>,
+}
+
+impl<'a> BkTree<'a> {
+    fn new(ids: &'a [&'a str], metric: Metric) -> Self {
+        let mut tree = BkTree {
+            ids,
+            metric,
+            nodes: Vec::with_capacity(ids.len()),
         };
 
-    a.chars()
-        .zip(b.chars())
-        .filter(|(a, b)| a == b)
-        .map(|(a, _)| a)
-        .collect()
+        for id in 0..ids.len() {
+            tree.insert(id);
+        }
+
+        tree
+    }
+
+    fn insert(&mut self, id: usize) {
+        let new_node = self.nodes.len();
+        self.nodes.push(BkNode {
+            id,
+            children: Vec::new(),
+        });
+        if new_node == 0 {
+            return;
+        }
+
+        let mut cur = 0;
+        loop {
+            let dist = self
+                .metric
+                .distance(self.ids[self.nodes[cur].id], self.ids[id]);
+
+            match self.nodes[cur].children.iter().find(|&&(d, _)| d == dist) {
+                Some(&(_, child)) => cur = child,
+                None => {
+                    self.nodes[cur].children.push((dist, new_node));
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Indices of every ID within `max_dist` of `query`, in ascending order.
+    fn within(&self, query: &str, max_dist: usize) -> Vec<usize> {
+        let mut found = Vec::new();
+        let mut stack = Vec::new();
+        if !self.nodes.is_empty() {
+            stack.push(0);
+        }
+
+        while let Some(node) = stack.pop() {
+            let node = &self.nodes[node];
+            let dist = self.metric.distance(query, self.ids[node.id]);
+            if dist <= max_dist {
+                found.push(node.id);
+            }
+
+            stack.extend(
+                node.children
+                    .iter()
+                    .filter(|&&(d, _)| d.abs_diff(dist) <= max_dist)
+                    .map(|&(_, child)| child),
+            );
+        }
+
+        found.sort_unstable();
+        found
+    }
+
+    /// Groups the IDs so that any two IDs within `max_dist` of each other end up in the same
+    /// cluster. Clusters are ordered by their lowest index, and each is in ascending order.
+    fn clusters(&self, max_dist: usize) -> Vec<Vec<usize>> {
+        let mut cluster_of = vec![None; self.ids.len()];
+        let mut clusters = Vec::new();
+
+        for start in 0..self.ids.len() {
+            if cluster_of[start].is_some() {
+                continue;
+            }
+
+            let cluster_idx = clusters.len();
+            cluster_of[start] = Some(cluster_idx);
+            let mut cluster = vec![start];
+            let mut pending = vec![start];
+
+            while let Some(id) = pending.pop() {
+                for near in self.within(self.ids[id], max_dist) {
+                    if cluster_of[near].is_none() {
+                        cluster_of[near] = Some(cluster_idx);
+                        cluster.push(near);
+                        pending.push(near);
+                    }
+                }
+            }
+
+            cluster.sort_unstable();
+            clusters.push(cluster);
+        }
+
+        clusters
+    }
+}
+
+/// Number of groups of more than one ID that are linked by single character differences.
+fn near_duplicate_groups(ids: &[&str], metric: Metric) -> usize {
+    BkTree::new(ids, metric)
+        .clusters(1)
+        .iter()
+        .filter(|c| c.len() > 1)
+        .count()
 }
 
 #[cfg(test)]
 mod tests {
+    use std::ops::Range;
+
     use super::*;
     use aoc_lib::Example;
 
+    use crate::days::rng::Rng;
+
+    /// IDs over a three letter alphabet, so that near matches are common.
+    fn random_ids(rng: &mut Rng, count: usize, lens: Range<usize>) -> Vec<String> {
+        (0..count)
+            .map(|_| {
+                let len = lens.start + rng.below(lens.len() as u64) as usize;
+                (0..len)
+                    .map(|_| (b'a' + rng.below(3) as u8) as char)
+                    .collect()
+            })
+            .collect()
+    }
+
     #[test]
     fn part1_test() {
         let data = aoc_lib::input(DAY.day)
@@ -97,6 +413,34 @@ mod tests {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn multiplicity_test() {
+        let long = "a".repeat(258);
+        let ids = ["aAbBc", "ÉéèÈè", "ßßßü", &long, "xyz"];
+
+        // A `u8` count would wrap the 258 `a`s around to 2.
+        assert_eq!(
+            [4, 1, 1, 0],
+            count_multiplicities(&ids, [1, 2, 3, 256], CasePolicy::Distinct)
+        );
+        assert_eq!(
+            [3, 2, 2, 1],
+            count_multiplicities(&ids, [1, 2, 3, 258], CasePolicy::Fold)
+        );
+        assert_eq!([1], count_multiplicities(&ids, [258], CasePolicy::Distinct));
+
+        assert_eq!(1, checksum(&ids, CasePolicy::Distinct));
+        assert_eq!(4, checksum(&ids, CasePolicy::Fold));
+    }
+
+    #[test]
+    fn unicode_part2_test() {
+        let parsed = parse("héllo\nwörld\nhallo\n").unwrap();
+        assert_eq!("hllo", part2(&parsed).unwrap());
+
+        assert!(parse("abc1").is_err());
+    }
+
     #[test]
     fn part2_test() {
         let data = aoc_lib::input(DAY.day)
@@ -106,8 +450,162 @@ mod tests {
 
         let parsed = parse(&data).unwrap();
         let expected = "fgij";
-        let actual = part2(&parsed);
+        let actual = part2(&parsed).unwrap();
 
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn near_matches_test() {
+        let ids = ["abcd", "abce", "abcd", "xbce", "abxe", "abc"];
+        let expected = [
+            NearMatch {
+                a: 0,
+                b: 1,
+                position: 3,
+            },
+            NearMatch {
+                a: 1,
+                b: 2,
+                position: 3,
+            },
+            NearMatch {
+                a: 1,
+                b: 3,
+                position: 0,
+            },
+            NearMatch {
+                a: 1,
+                b: 4,
+                position: 2,
+            },
+        ];
+
+        assert_eq!(expected, *near_matches(&ids));
+        assert!(part2(&["abc", "xyz", "abc"]).is_err());
+    }
+
+    #[test]
+    fn near_matches_brute_force_test() {
+        let owned = random_ids(&mut Rng::new(), 300, 3..5);
+        let ids: Vec<&str> = owned.iter().map(String::as_str).collect();
+
+        let mut expected = Vec::new();
+        for (a, a_id) in ids.iter().enumerate() {
+            for (b, b_id) in ids.iter().enumerate().skip(a + 1) {
+                if a_id.len() != b_id.len() {
+                    continue;
+                }
+
+                let diffs: Vec<_> = a_id
+                    .bytes()
+                    .zip(b_id.bytes())
+                    .enumerate()
+                    .filter(|(_, (x, y))| x != y)
+                    .map(|(i, _)| i)
+                    .collect();
+                if let [position] = *diffs {
+                    expected.push(NearMatch { a, b, position });
+                }
+            }
+        }
+
+        assert!(!expected.is_empty());
+        assert_eq!(expected, near_matches(&ids));
+    }
+
+    #[test]
+    fn distance_test() {
+        let tests = [
+            ("kitten", "sitting", 3, 3),
+            ("flaw", "lawn", 2, 4),
+            ("abcd", "abcd", 0, 0),
+            ("", "abc", 3, 3),
+            ("abc", "abd", 1, 1),
+        ];
+
+        for (a, b, levenshtein, hamming) in tests {
+            assert_eq!(levenshtein, Metric::Levenshtein.distance(a, b), "{a} {b}");
+            assert_eq!(levenshtein, Metric::Levenshtein.distance(b, a), "{b} {a}");
+            assert_eq!(hamming, Metric::Hamming.distance(a, b), "{a} {b}");
+            assert_eq!(hamming, Metric::Hamming.distance(b, a), "{b} {a}");
+        }
+    }
+
+    #[test]
+    fn bk_tree_brute_force_test() {
+        let owned = random_ids(&mut Rng::new(), 200, 3..7);
+        let ids: Vec<&str> = owned.iter().map(String::as_str).collect();
+
+        for metric in [Metric::Hamming, Metric::Levenshtein] {
+            let tree = BkTree::new(&ids, metric);
+
+            for max_dist in 0..=3 {
+                for query in ids.iter().take(20).chain(&["", "abcabc", "zzz"]) {
+                    let expected: Vec<_> = (0..ids.len())
+                        .filter(|&i| metric.distance(query, ids[i]) <= max_dist)
+                        .collect();
+
+                    assert_eq!(expected, tree.within(query, max_dist), "{metric:?} {query}");
+                }
+            }
+
+            for max_dist in 0..=2 {
+                // Label each ID by flooding the brute-force neighbour graph.
+                let mut labels: Vec<usize> = (0..ids.len()).collect();
+                let mut changed = true;
+                while changed {
+                    changed = false;
+                    for a in 0..ids.len() {
+                        for b in 0..ids.len() {
+                            if labels[b] < labels[a] && metric.distance(ids[a], ids[b]) <= max_dist
+                            {
+                                labels[a] = labels[b];
+                                changed = true;
+                            }
+                        }
+                    }
+                }
+
+                let mut expected: Vec<Vec<usize>> = Vec::new();
+                for (id, &label) in labels.iter().enumerate() {
+                    match expected.iter_mut().find(|c| labels[c[0]] == label) {
+                        Some(cluster) => cluster.push(id),
+                        None => expected.push(vec![id]),
+                    }
+                }
+
+                assert_eq!(expected, tree.clusters(max_dist), "{metric:?} {max_dist}");
+            }
+        }
+    }
+
+    #[test]
+    fn bk_tree_part2_test() {
+        let data = aoc_lib::input(DAY.day)
+            .example(Example::Part2, 1)
+            .open()
+            .unwrap();
+
+        let parsed = parse(&data).unwrap();
+        let tree = BkTree::new(&parsed, Metric::Hamming);
+
+        let (a, b) = parsed
+            .iter()
+            .enumerate()
+            .find_map(|(a, id)| {
+                let b = tree.within(id, 1).into_iter().find(|&b| b > a)?;
+                Some((a, b))
+            })
+            .unwrap();
+        let common: String = parsed[a]
+            .chars()
+            .zip(parsed[b].chars())
+            .filter(|(a, b)| a == b)
+            .map(|(a, _)| a)
+            .collect();
+
+        assert_eq!(part2(&parsed).unwrap(), common);
+        assert_eq!(1, near_duplicate_groups(&parsed, Metric::Hamming));
+    }
 }
@@ -1,4 +1,4 @@
-use std::ops::Range;
+use std::{collections::BTreeSet, ops::Range};
 
 use aoc_lib::{Bench, BenchResult, Day, NoError, ParseResult, UserError};
 use chumsky::{extra::Default, primitive::just, text::int, Parser};
@@ -19,7 +19,7 @@ fn run_part1(input: &str, b: Bench) -> BenchResult {
 
 fn run_part2(input: &str, b: Bench) -> BenchResult {
     let data = parse(input).map_err(UserError)?;
-    b.bench(|| Ok::<_, NoError>(part2(&data)))
+    b.bench(|| part2(&data))
 }
 
 fn run_parse(input: &str, b: Bench) -> BenchResult {
@@ -31,16 +31,17 @@ fn run_parse(input: &str, b: Bench) -> BenchResult {
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 struct Rectangle {
-    id: u16,
-    x: u16,
-    y: u16,
-    width: u16,
-    height: u16,
+    id: u32,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
 }
 
 impl Rectangle {
     fn parse(input: &str) -> Result<Rectangle> {
-        let number = int::<_, _, Default>(10).from_str::<u16>().unwrapped();
+        // Keep the digits as text, so numbers too big for the fabric are an error, not a panic.
+        let number = int::<_, _, Default>(10);
         let id = just('#').ignore_then(number);
         let xy_pair = number.then_ignore(just(',')).then(number);
         let size_pair = number.then_ignore(just('x')).then(number);
@@ -56,6 +57,22 @@ impl Rectangle {
             .into_result()
             .map_err(|e| eyre!("Error(s) parsing claim: {e:?}"))?;
 
+        let id: u32 = id
+            .parse()
+            .map_err(|_| eyre!("Claim ID #{id} is out of range"))?;
+        let number = |n: &str| {
+            n.parse::<u32>()
+                .map_err(|_| eyre!("Claim #{id} has out of range value {n}"))
+        };
+        let (x, y, width, height) = (number(x)?, number(y)?, number(width)?, number(height)?);
+
+        if width == 0 || height == 0 {
+            return Err(eyre!("Claim #{id} is empty"));
+        }
+        if x.checked_add(width).is_none() || y.checked_add(height).is_none() {
+            return Err(eyre!("Claim #{id} extends past the edge of the fabric"));
+        }
+
         Ok(Rectangle {
             id,
             x,
@@ -65,11 +82,11 @@ impl Rectangle {
         })
     }
 
-    fn x_range(&self) -> Range<u16> {
+    fn x_range(&self) -> Range<u32> {
         self.x..self.x + self.width
     }
 
-    fn y_range(&self) -> Range<u16> {
+    fn y_range(&self) -> Range<u32> {
         self.y..self.y + self.height
     }
 }
@@ -78,42 +95,390 @@ fn parse(input: &str) -> Result<Vec<Rectangle>> {
     input.lines().map(str::trim).map(Rectangle::parse).collect()
 }
 
-fn part1(claims: &[Rectangle]) -> usize {
-    let mut fabric = vec![0; 1000 * 1000];
+/// A segment tree over the gaps between the distinct y coordinates of the claims, tracking how
+/// much of the fabric along the sweep line is currently claimed at least once and at least twice.
+#[derive(Debug)]
+struct CoverTree<'a> {
+    ys: &'a [u32],
+    /// Number of active claims that cover the whole of each node's range.
+    count: Vec<u32>,
+    once: Vec<usize>,
+    twice: Vec<usize>,
+}
+
+impl<'a> CoverTree<'a> {
+    fn new(ys: &'a [u32]) -> Self {
+        let nodes = 4 * ys.len().max(1);
+        Self {
+            ys,
+            count: vec![0; nodes],
+            once: vec![0; nodes],
+            twice: vec![0; nodes],
+        }
+    }
+
+    fn segments(&self) -> usize {
+        self.ys.len().saturating_sub(1)
+    }
+
+    /// Index of the segment range covered by `range`.
+    fn segment_range(&self, range: Range<u32>) -> Range<usize> {
+        let idx = |y| self.ys.binary_search(&y).unwrap_or_else(|i| i);
+        idx(range.start)..idx(range.end)
+    }
+
+    fn add(&mut self, range: Range<u32>, claimed: bool) {
+        let segments = self.segment_range(range);
+        self.update(1, 0..self.segments(), &segments, claimed);
+    }
+
+    fn update(&mut self, node: usize, span: Range<usize>, target: &Range<usize>, claimed: bool) {
+        if span.end <= target.start || target.end <= span.start || span.is_empty() {
+            return;
+        }
+
+        if target.start <= span.start && span.end <= target.end {
+            if claimed {
+                self.count[node] += 1;
+            } else {
+                self.count[node] -= 1;
+            }
+        } else {
+            let mid = (span.start + span.end) / 2;
+            self.update(2 * node, span.start..mid, target, claimed);
+            self.update(2 * node + 1, mid..span.end, target, claimed);
+        }
+
+        let full = (self.ys[span.end] - self.ys[span.start]) as usize;
+        let (once, twice) = if span.len() == 1 {
+            (0, 0)
+        } else {
+            (
+                self.once[2 * node] + self.once[2 * node + 1],
+                self.twice[2 * node] + self.twice[2 * node + 1],
+            )
+        };
+
+        (self.once[node], self.twice[node]) = match self.count[node] {
+            0 => (once, twice),
+            1 => (full, once),
+            _ => (full, full),
+        };
+    }
+
+    /// Whether any active claim covers part of `range`.
+    fn any_claimed(&self, range: Range<u32>) -> bool {
+        let segments = self.segment_range(range);
+        self.query(1, 0..self.segments(), &segments)
+    }
 
-    for claim in claims {
-        for y in claim.y_range() {
-            let start = y as usize * 1000 + claim.x as usize;
-            let range = start..start + claim.width as usize;
-            fabric[range].iter_mut().for_each(|i| *i += 1);
+    fn query(&self, node: usize, span: Range<usize>, target: &Range<usize>) -> bool {
+        if span.end <= target.start || target.end <= span.start || span.is_empty() {
+            return false;
+        }
+        if self.count[node] > 0 {
+            return true;
         }
+        if target.start <= span.start && span.end <= target.end {
+            return self.once[node] > 0;
+        }
+
+        let mid = (span.start + span.end) / 2;
+        self.query(2 * node, span.start..mid, target)
+            || self.query(2 * node + 1, mid..span.end, target)
     }
 
-    fabric.into_iter().filter(|&i| i >= 2).count()
+    fn claimed_twice(&self) -> usize {
+        self.twice[1]
+    }
 }
 
-fn part2(claims: &[Rectangle]) -> u16 {
-    let mut overlaps = vec![false; claims.len()];
-    for (i, c1) in claims.iter().enumerate() {
-        for (j, c2) in claims[i + 1..].iter().enumerate() {
-            let x_overlap = c1.x_range().contains(&c2.x) | c2.x_range().contains(&c1.x);
-            let y_overlap = c1.y_range().contains(&c2.y) | c2.y_range().contains(&c1.y);
-            if x_overlap & y_overlap {
-                overlaps[i] = true;
-                overlaps[i + j + 1] = true;
+/// The distinct y coordinates where a claim starts or ends.
+fn y_coordinates(claims: &[Rectangle]) -> Vec<u32> {
+    let mut ys: Vec<_> = claims
+        .iter()
+        .flat_map(|c| {
+            let ys = c.y_range();
+            [ys.start, ys.end]
+        })
+        .collect();
+    ys.sort_unstable();
+    ys.dedup();
+    ys
+}
+
+/// Claims along with where they start and end on the x axis, ordered by x with ends before starts.
+fn x_events(claims: &[Rectangle]) -> Vec<(u32, bool, usize)> {
+    let mut events: Vec<_> = claims
+        .iter()
+        .enumerate()
+        .flat_map(|(idx, c)| {
+            let xs = c.x_range();
+            [(xs.start, true, idx), (xs.end, false, idx)]
+        })
+        .collect();
+    events.sort_unstable();
+    events
+}
+
+/// Sweeps a vertical line across the fabric, adding up the length of it that's claimed twice
+/// between each pair of claim edges.
+fn multiply_claimed_area(claims: &[Rectangle]) -> usize {
+    let ys = y_coordinates(claims);
+    let mut cover = CoverTree::new(&ys);
+    let mut area = 0;
+    let mut last_x = 0;
+
+    for (x, is_start, idx) in x_events(claims) {
+        area += cover.claimed_twice() * (x - last_x) as usize;
+        last_x = x;
+        cover.add(claims[idx].y_range(), is_start);
+    }
+
+    area
+}
+
+/// Claims active on the sweep line that haven't been found to overlap anything yet, so that each
+/// can be found and removed once it does.
+#[derive(Debug)]
+struct Unmatched {
+    /// Claims stored at the segment tree nodes that make up their y range.
+    nodes: Vec<Vec<usize>>,
+    /// Claims keyed by where they start on the y axis.
+    starts: BTreeSet<(u32, usize)>,
+}
+
+impl Unmatched {
+    fn insert(&mut self, cover: &CoverTree, claims: &[Rectangle], idx: usize) {
+        let segments = cover.segment_range(claims[idx].y_range());
+        self.insert_node(1, 0..cover.segments(), &segments, idx);
+        self.starts.insert((claims[idx].y, idx));
+    }
+
+    fn insert_node(&mut self, node: usize, span: Range<usize>, target: &Range<usize>, idx: usize) {
+        if span.end <= target.start || target.end <= span.start || span.is_empty() {
+            return;
+        }
+        if target.start <= span.start && span.end <= target.end {
+            self.nodes[node].push(idx);
+            return;
+        }
+
+        let mid = (span.start + span.end) / 2;
+        self.insert_node(2 * node, span.start..mid, target, idx);
+        self.insert_node(2 * node + 1, mid..span.end, target, idx);
+    }
+
+    /// Removes and returns every claim whose y range overlaps `range`. Claims that ended or were
+    /// already matched are skipped using `alive`.
+    fn take_overlapping(
+        &mut self,
+        cover: &CoverTree,
+        range: Range<u32>,
+        alive: impl Fn(usize) -> bool,
+    ) -> Vec<usize> {
+        let mut found = Vec::new();
+
+        // Claims covering the start of the range are on the path down to its first segment.
+        let segment = cover.segment_range(range.start..range.start).start;
+        let (mut node, mut span) = (1, 0..cover.segments());
+        while !range.is_empty() && segment < span.end && !span.is_empty() {
+            found.extend(self.nodes[node].drain(..).filter(|&idx| alive(idx)));
+            if span.len() == 1 {
+                break;
             }
+
+            let mid = (span.start + span.end) / 2;
+            (node, span) = if segment < mid {
+                (2 * node, span.start..mid)
+            } else {
+                (2 * node + 1, mid..span.end)
+            };
+        }
+
+        // The rest start somewhere inside the range.
+        let starting: Vec<_> = self
+            .starts
+            .range((range.start + 1, 0)..(range.end, 0))
+            .copied()
+            .collect();
+        for key in starting {
+            self.starts.remove(&key);
+            found.push(key.1);
+        }
+
+        found.retain(|&idx| alive(idx));
+        found.sort_unstable();
+        found.dedup();
+        found
+    }
+
+    fn remove_start(&mut self, claim: &Rectangle, idx: usize) {
+        self.starts.remove(&(claim.y, idx));
+    }
+}
+
+/// Sweeps a vertical line across the fabric to find the claims that don't overlap any other.
+///
+/// A claim starting on the sweep line overlaps another if any of the line it covers is already
+/// claimed. The claims it overlaps are marked too, but only those not already known to overlap
+/// something are looked up, so each claim is found at most once.
+fn isolated_claims(claims: &[Rectangle]) -> Vec<u32> {
+    let ys = y_coordinates(claims);
+    let mut cover = CoverTree::new(&ys);
+    let mut unmatched = Unmatched {
+        nodes: vec![Vec::new(); cover.count.len()],
+        starts: BTreeSet::new(),
+    };
+    let mut active = vec![false; claims.len()];
+    let mut overlaps = vec![false; claims.len()];
+
+    for (_, is_start, idx) in x_events(claims) {
+        let claim = &claims[idx];
+
+        if !is_start {
+            active[idx] = false;
+            cover.add(claim.y_range(), false);
+            unmatched.remove_start(claim, idx);
+            continue;
+        }
+
+        let found = unmatched.take_overlapping(&cover, claim.y_range(), |other| {
+            active[other] && !overlaps[other]
+        });
+        for &other in &found {
+            overlaps[other] = true;
+        }
+        overlaps[idx] = !found.is_empty() || cover.any_claimed(claim.y_range());
+
+        active[idx] = true;
+        cover.add(claim.y_range(), true);
+        if !overlaps[idx] {
+            unmatched.insert(&cover, claims, idx);
         }
     }
 
-    let idx = overlaps.iter().position(|&i| !i).unwrap();
-    claims[idx].id
+    claims
+        .iter()
+        .zip(overlaps)
+        .filter(|(_, overlaps)| !overlaps)
+        .map(|(c, _)| c.id)
+        .collect()
+}
+
+fn part1(claims: &[Rectangle]) -> usize {
+    multiply_claimed_area(claims)
+}
+
+fn part2(claims: &[Rectangle]) -> Result<u32> {
+    isolated_claims(claims)
+        .first()
+        .copied()
+        .ok_or_else(|| eyre!("Every claim overlaps another"))
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+
     use super::*;
     use aoc_lib::Example;
 
+    use crate::days::rng::Rng;
+
+    /// Bounding boxes bigger than this are counted sparsely instead of with a grid.
+    const MAX_DENSE_AREA: usize = 1 << 24;
+
+    /// The smallest area of fabric covering every claim.
+    #[derive(Debug, Clone)]
+    struct Bounds {
+        x: Range<u32>,
+        y: Range<u32>,
+    }
+
+    impl Bounds {
+        fn of(claims: &[Rectangle]) -> Option<Bounds> {
+            claims
+                .iter()
+                .map(|c| Bounds {
+                    x: c.x_range(),
+                    y: c.y_range(),
+                })
+                .reduce(|a, b| Bounds {
+                    x: a.x.start.min(b.x.start)..a.x.end.max(b.x.end),
+                    y: a.y.start.min(b.y.start)..a.y.end.max(b.y.end),
+                })
+        }
+
+        fn area(&self) -> usize {
+            self.x.len() * self.y.len()
+        }
+    }
+
+    /// Counts every square inch, on a grid covering the claims if it's small enough.
+    fn reference_area(claims: &[Rectangle]) -> usize {
+        let Some(bounds) = Bounds::of(claims) else {
+            return 0;
+        };
+
+        if bounds.area() > MAX_DENSE_AREA {
+            return reference_area_sparse(claims);
+        }
+
+        // We only care whether an inch is claimed more than once, so the counts can stop at 2.
+        let mut fabric = vec![0u8; bounds.area()];
+        let width = bounds.x.len();
+
+        for claim in claims {
+            for y in claim.y_range() {
+                let start =
+                    (y - bounds.y.start) as usize * width + (claim.x - bounds.x.start) as usize;
+                let range = start..start + claim.width as usize;
+                fabric[range].iter_mut().for_each(|i| *i = (*i + 1).min(2));
+            }
+        }
+
+        fabric.into_iter().filter(|&i| i >= 2).count()
+    }
+
+    /// Only tracks the inches that are actually claimed, for claims spread over a huge area.
+    fn reference_area_sparse(claims: &[Rectangle]) -> usize {
+        let mut fabric: HashMap<(u32, u32), u8> = HashMap::new();
+
+        for claim in claims {
+            for y in claim.y_range() {
+                for x in claim.x_range() {
+                    let count = fabric.entry((x, y)).or_default();
+                    *count = (*count + 1).min(2);
+                }
+            }
+        }
+
+        fabric.into_values().filter(|&i| i >= 2).count()
+    }
+
+    /// Checks every pair of claims.
+    fn reference_isolated(claims: &[Rectangle]) -> Vec<u32> {
+        let mut overlaps = vec![false; claims.len()];
+        for (i, c1) in claims.iter().enumerate() {
+            for (j, c2) in claims[i + 1..].iter().enumerate() {
+                let x_overlap = c1.x_range().contains(&c2.x) | c2.x_range().contains(&c1.x);
+                let y_overlap = c1.y_range().contains(&c2.y) | c2.y_range().contains(&c1.y);
+                if x_overlap & y_overlap {
+                    overlaps[i] = true;
+                    overlaps[i + j + 1] = true;
+                }
+            }
+        }
+
+        claims
+            .iter()
+            .zip(overlaps)
+            .filter(|(_, overlaps)| !overlaps)
+            .map(|(c, _)| c.id)
+            .collect()
+    }
+
     #[test]
     fn part1_test() {
         let data = aoc_lib::input(DAY.day)
@@ -126,6 +491,7 @@ mod tests {
         let actual = part1(&parsed);
 
         assert_eq!(expected, actual);
+        assert_eq!(expected, reference_area(&parsed));
     }
 
     #[test]
@@ -137,8 +503,101 @@ mod tests {
 
         let parsed = parse(&data).unwrap();
         let expected = 3;
-        let actual = part2(&parsed);
+        let actual = part2(&parsed).unwrap();
 
         assert_eq!(expected, actual);
+        assert_eq!([expected], *reference_isolated(&parsed));
+    }
+
+    #[test]
+    fn large_coordinates_test() {
+        let claims = "\
+#1 @ 1500,2500: 10x10
+#2 @ 1505,2505: 10x10
+#3 @ 1000,3000: 2x2";
+        let parsed = parse(claims).unwrap();
+        assert!(Bounds::of(&parsed).unwrap().area() <= MAX_DENSE_AREA);
+        assert_eq!(25, part1(&parsed));
+        assert_eq!(25, reference_area(&parsed));
+        assert_eq!(3, part2(&parsed).unwrap());
+
+        let claims = "\
+#1 @ 60000,60000: 10x10
+#2 @ 60005,60005: 10x10
+#3 @ 0,0: 2x2
+#4 @ 1,1: 2x2
+#5 @ 65525,0: 10x65535";
+        let parsed = parse(claims).unwrap();
+        assert!(Bounds::of(&parsed).unwrap().area() > MAX_DENSE_AREA);
+        assert_eq!(26, part1(&parsed));
+        assert_eq!(26, reference_area(&parsed));
+        assert_eq!([5], *isolated_claims(&parsed));
+    }
+
+    #[test]
+    fn out_of_range_test() {
+        assert!(Rectangle::parse("#1 @ 4294967290,0: 10x10").is_err());
+        assert!(Rectangle::parse("#1 @ 0,4294967295: 1x1").is_err());
+        assert!(Rectangle::parse("#1 @ 5000000000,5: 3x3").is_err());
+        assert!(Rectangle::parse("#1 @ 5,5: 3x99999999999999999999").is_err());
+        assert!(Rectangle::parse("#5000000000 @ 5,5: 3x3").is_err());
+        assert!(Rectangle::parse("#1 @ 4294967285,4294967285: 10x10").is_ok());
+        assert!(Rectangle::parse("#70000 @ 1000000,5: 3x3").is_ok());
+    }
+
+    #[test]
+    fn empty_claim_test() {
+        assert!(Rectangle::parse("#2 @ 2,2: 0x0").is_err());
+        assert!(Rectangle::parse("#2 @ 2,2: 3x0").is_err());
+        assert!(parse("#1 @ 0,0: 5x5\n#2 @ 2,2: 0x0\n#3 @ 10,10: 1x1").is_err());
+    }
+
+    #[test]
+    fn many_claims_test() {
+        // Pairs of claims overlapping in a 5x5 square, laid out on a grid far from the origin,
+        // plus one claim on its own at the end.
+        let pairs = 35_000;
+        let mut input = String::new();
+        for pair in 0..pairs {
+            let x = 1_000_000 + 20 * (pair % 300);
+            let y = 2_000_000 + 20 * (pair / 300);
+            input += &format!("#{} @ {x},{y}: 10x10\n", 2 * pair + 1);
+            input += &format!("#{} @ {},{}: 10x10\n", 2 * pair + 2, x + 5, y + 5);
+        }
+        input += &format!("#{} @ 3000000,3000000: 1x1", 2 * pairs + 1);
+
+        let parsed = parse(&input).unwrap();
+        assert!(parsed.len() > 65535);
+        assert_eq!(25 * pairs as usize, part1(&parsed));
+        assert_eq!([2 * pairs + 1], *isolated_claims(&parsed));
+    }
+
+    #[test]
+    fn all_overlapping_test() {
+        let parsed = parse("#1 @ 0,0: 2x2\n#2 @ 1,1: 2x2").unwrap();
+        assert!(part2(&parsed).is_err());
+    }
+
+    #[test]
+    fn sweep_reference_test() {
+        let mut rng = Rng::new();
+        let mut next = |modulus: u64| rng.below(modulus) as u32;
+
+        for (count, extent, max_size) in [(10, 20, 5), (100, 200, 20), (400, 1000, 30)] {
+            for _ in 0..10 {
+                let claims: Vec<_> = (1..=count)
+                    .map(|id| Rectangle {
+                        id,
+                        x: next(extent),
+                        y: next(extent),
+                        width: next(max_size) + 1,
+                        height: next(max_size) + 1,
+                    })
+                    .collect();
+
+                assert_eq!(reference_area(&claims), multiply_claimed_area(&claims));
+                assert_eq!(reference_isolated(&claims), isolated_claims(&claims));
+            }
+        }
     }
 }
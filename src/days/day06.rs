@@ -0,0 +1,192 @@
+use aoc_lib::{Bench, BenchResult, Day, NoError, ParseResult, UserError};
+use color_eyre::{eyre::eyre, Report, Result};
+
+pub const DAY: Day = Day {
+    day: 6,
+    name: "Chronal Coordinates",
+    part_1: run_part1,
+    part_2: Some(run_part2),
+    other: &[("Parse", run_parse)],
+};
+
+fn run_part1(input: &str, b: Bench) -> BenchResult {
+    let data = parse(input).map_err(UserError)?;
+    b.bench(|| Ok::<_, NoError>(part1(&data)))
+}
+
+fn run_part2(input: &str, b: Bench) -> BenchResult {
+    let data = parse(input).map_err(UserError)?;
+    b.bench(|| Ok::<_, NoError>(part2::<10000>(&data)))
+}
+
+fn run_parse(input: &str, b: Bench) -> BenchResult {
+    b.bench(|| {
+        let data = parse(input).map_err(UserError)?;
+        Ok::<_, Report>(ParseResult(data))
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+impl Point {
+    fn parse(line: &str) -> Result<Point> {
+        let (x, y) = line
+            .split_once(',')
+            .ok_or_else(|| eyre!("invalid coordinate: `{line}`"))?;
+
+        Ok(Point {
+            x: x.trim().parse()?,
+            y: y.trim().parse()?,
+        })
+    }
+
+    fn distance(self, x: i32, y: i32) -> u32 {
+        self.x.abs_diff(x) + self.y.abs_diff(y)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bounds {
+    min_x: i32,
+    max_x: i32,
+    min_y: i32,
+    max_y: i32,
+}
+
+impl Bounds {
+    fn of(points: &[Point]) -> Bounds {
+        points.iter().fold(
+            Bounds {
+                min_x: i32::MAX,
+                max_x: i32::MIN,
+                min_y: i32::MAX,
+                max_y: i32::MIN,
+            },
+            |b, p| Bounds {
+                min_x: b.min_x.min(p.x),
+                max_x: b.max_x.max(p.x),
+                min_y: b.min_y.min(p.y),
+                max_y: b.max_y.max(p.y),
+            },
+        )
+    }
+
+    fn is_edge(&self, x: i32, y: i32) -> bool {
+        x == self.min_x || x == self.max_x || y == self.min_y || y == self.max_y
+    }
+}
+
+fn parse(input: &str) -> Result<Vec<Point>> {
+    let points: Vec<_> = input
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(Point::parse)
+        .collect::<Result<_>>()?;
+
+    if points.is_empty() {
+        return Err(eyre!("no coordinates in input"));
+    }
+
+    Ok(points)
+}
+
+fn closest(points: &[Point], x: i32, y: i32) -> Option<usize> {
+    let mut best = None;
+    let mut best_dist = u32::MAX;
+    let mut tied = false;
+
+    for (i, p) in points.iter().enumerate() {
+        let dist = p.distance(x, y);
+        if dist < best_dist {
+            best = Some(i);
+            best_dist = dist;
+            tied = false;
+        } else if dist == best_dist {
+            tied = true;
+        }
+    }
+
+    if tied {
+        None
+    } else {
+        best
+    }
+}
+
+fn part1(points: &[Point]) -> u32 {
+    let bounds = Bounds::of(points);
+    let mut areas = vec![0; points.len()];
+    let mut infinite = vec![false; points.len()];
+
+    for y in bounds.min_y..=bounds.max_y {
+        for x in bounds.min_x..=bounds.max_x {
+            let Some(owner) = closest(points, x, y) else {
+                continue;
+            };
+
+            // Any region touching the bounding box keeps growing outwards forever.
+            if bounds.is_edge(x, y) {
+                infinite[owner] = true;
+            }
+            areas[owner] += 1;
+        }
+    }
+
+    areas
+        .into_iter()
+        .zip(infinite)
+        .filter(|(_, inf)| !inf)
+        .map(|(area, _)| area)
+        .max()
+        .unwrap_or(0)
+}
+
+fn part2<const LIMIT: u32>(points: &[Point]) -> usize {
+    let bounds = Bounds::of(points);
+    // A cell further than this outside the bounding box has a total distance of at least LIMIT.
+    let margin = (LIMIT / points.len() as u32) as i32;
+
+    (bounds.min_y - margin..=bounds.max_y + margin)
+        .flat_map(|y| (bounds.min_x - margin..=bounds.max_x + margin).map(move |x| (x, y)))
+        .filter(|&(x, y)| points.iter().map(|p| p.distance(x, y)).sum::<u32>() < LIMIT)
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_lib::Example;
+
+    #[test]
+    fn part1_test() {
+        let data = aoc_lib::input(DAY.day)
+            .example(Example::Part1, 1)
+            .open()
+            .unwrap();
+
+        let parsed = parse(&data).unwrap();
+        let expected = 17;
+        let actual = part1(&parsed);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn part2_test() {
+        let data = aoc_lib::input(DAY.day)
+            .example(Example::Part1, 1)
+            .open()
+            .unwrap();
+
+        let parsed = parse(&data).unwrap();
+        let expected = 16;
+        let actual = part2::<32>(&parsed);
+
+        assert_eq!(expected, actual);
+    }
+}
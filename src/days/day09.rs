@@ -5,7 +5,7 @@ pub const DAY: Day = Day {
     day: 9,
     name: "Marble Mania",
     part_1: run_part1,
-    part_2: None,
+    part_2: Some(run_part2),
     other: &[("Parse", run_parse)],
 };
 
@@ -14,6 +14,11 @@ fn run_part1(input: &str, b: Bench) -> BenchResult {
     b.bench(|| Ok::<_, NoError>(part1(data)))
 }
 
+fn run_part2(input: &str, b: Bench) -> BenchResult {
+    let data = parse(input).map_err(UserError)?;
+    b.bench(|| Ok::<_, NoError>(part2(data)))
+}
+
 fn run_parse(input: &str, b: Bench) -> BenchResult {
     b.bench(|| {
         let data = parse(input).map_err(UserError)?;
@@ -44,61 +49,60 @@ fn parse(input: &str) -> Result<Game> {
     })
 }
 
+/// The marble circle, stored as a ring of indices where each marble's value is its own index.
 #[derive(Debug)]
 struct Playground {
-    board: Vec<usize>,
-    cursor: usize,
+    next: Vec<u32>,
+    prev: Vec<u32>,
+    current: u32,
 }
 
 impl Playground {
     fn new(marbles: usize) -> Self {
         Self {
-            board: {
-                let mut v = Vec::with_capacity(marbles);
-                v.push(0);
-                v
-            },
-            cursor: 0,
+            next: vec![0; marbles + 1],
+            prev: vec![0; marbles + 1],
+            current: 0,
         }
     }
 
+    fn insert_after(&mut self, after: u32, marble: u32) {
+        let before = self.next[after as usize];
+        self.next[after as usize] = marble;
+        self.prev[before as usize] = marble;
+        self.next[marble as usize] = before;
+        self.prev[marble as usize] = after;
+    }
+
+    fn remove(&mut self, marble: u32) {
+        let prev = self.prev[marble as usize];
+        let next = self.next[marble as usize];
+        self.next[prev as usize] = next;
+        self.prev[next as usize] = prev;
+    }
+
     fn play(&mut self, marble: usize) -> Option<usize> {
         if marble % 23 == 0 {
-            let cursor_pos = match self.cursor.checked_sub(7) {
-                Some(cp) => cp,
-                None => self.cursor + self.board.len() - 7,
-            };
+            let removed = (0..7).fold(self.current, |m, _| self.prev[m as usize]);
+            self.remove(removed);
+            self.current = self.next[removed as usize];
 
-            let removed = self.board.remove(cursor_pos);
-            self.cursor = cursor_pos;
-
-            Some(removed)
-        } else if self.board.len() == 1 {
-            self.board.push(marble);
-            self.cursor = 1;
-            None
+            Some(removed as usize)
         } else {
-            let insert_pos = self.cursor + 2;
-            let insert_pos = if insert_pos == self.board.len() {
-                self.board.push(marble);
-                insert_pos
-            } else {
-                let insert_pos = (self.cursor + 2) % self.board.len();
-                self.board.insert(insert_pos, marble);
-                insert_pos
-            };
-            self.cursor = insert_pos;
+            let marble = marble as u32;
+            self.insert_after(self.next[self.current as usize], marble);
+            self.current = marble;
             None
         }
     }
 }
 
-fn part1(data: Game) -> usize {
-    let mut playground = Playground::new(data.marbles);
-    let mut scores = vec![0; data.players];
+fn play_game(players: usize, marbles: usize) -> usize {
+    let mut playground = Playground::new(marbles);
+    let mut scores = vec![0; players];
     let player_iter = (0..scores.len()).cycle();
 
-    for (marble, player_id) in (1..=data.marbles).zip(player_iter) {
+    for (marble, player_id) in (1..=marbles).zip(player_iter) {
         if let Some(removed_marble) = playground.play(marble) {
             scores[player_id] += removed_marble + marble;
         }
@@ -107,23 +111,71 @@ fn part1(data: Game) -> usize {
     scores.into_iter().max().unwrap()
 }
 
+fn part1(data: Game) -> usize {
+    play_game(data.players, data.marbles)
+}
+
+fn part2(data: Game) -> usize {
+    play_game(data.players, data.marbles * 100)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use aoc_lib::Example;
 
+    impl Playground {
+        fn from_board(board: &[usize], cursor: usize) -> Self {
+            let len = board.iter().max().unwrap() + 1;
+            let mut playground = Self {
+                next: vec![0; len],
+                prev: vec![0; len],
+                current: board[cursor] as u32,
+            };
+
+            for (i, &marble) in board.iter().enumerate() {
+                let next = board[(i + 1) % board.len()];
+                playground.next[marble] = next as u32;
+                playground.prev[next] = marble as u32;
+            }
+
+            playground
+        }
+
+        fn board(&self) -> Vec<usize> {
+            let mut board = vec![0];
+            let mut marble = self.next[0];
+            while marble != 0 {
+                board.push(marble as usize);
+                marble = self.next[marble as usize];
+            }
+            board
+        }
+    }
+
     #[test]
     fn wrap_test() {
-        let mut playground = Playground {
-            board: vec![0, 1, 2, 3, 4, 5, 6, 7],
-            cursor: 2,
-        };
+        let mut playground = Playground::from_board(&[0, 1, 2, 3, 4, 5, 6, 7], 2);
 
         let expected = Some(3);
         let actual = playground.play(23);
         assert_eq!(actual, expected);
-        assert_eq!(playground.board, vec![0, 1, 2, 4, 5, 6, 7]);
-        assert_eq!(playground.cursor, 3);
+        assert_eq!(playground.board(), vec![0, 1, 2, 4, 5, 6, 7]);
+        assert_eq!(playground.current, 4);
+    }
+
+    #[test]
+    fn board_test() {
+        let mut playground = Playground::new(25);
+        for marble in 1..=25 {
+            playground.play(marble);
+        }
+
+        let expected = vec![
+            0, 16, 8, 17, 4, 18, 19, 2, 24, 20, 25, 10, 21, 5, 22, 11, 1, 12, 6, 13, 3, 14, 7, 15,
+        ];
+        assert_eq!(playground.board(), expected);
+        assert_eq!(playground.current, 25);
     }
 
     #[test]
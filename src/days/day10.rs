@@ -0,0 +1,219 @@
+use aoc_lib::{Bench, BenchResult, Day, NoError, ParseResult, UserError};
+use chumsky::{primitive::just, text::digits, Parser as _};
+use color_eyre::{eyre::eyre, Report, Result};
+
+use crate::Parser;
+
+pub const DAY: Day = Day {
+    day: 10,
+    name: "The Stars Align",
+    part_1: run_part1,
+    part_2: Some(run_part2),
+    other: &[("Parse", run_parse)],
+};
+
+fn run_part1(input: &str, b: Bench) -> BenchResult {
+    let data = parse(input).map_err(UserError)?;
+    b.bench(|| Ok::<_, NoError>(part1(&data)))
+}
+
+fn run_part2(input: &str, b: Bench) -> BenchResult {
+    let data = parse(input).map_err(UserError)?;
+    b.bench(|| Ok::<_, NoError>(part2(&data)))
+}
+
+fn run_parse(input: &str, b: Bench) -> BenchResult {
+    b.bench(|| {
+        let data = parse(input).map_err(UserError)?;
+        Ok::<_, Report>(ParseResult(data))
+    })
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Star {
+    x: i64,
+    y: i64,
+    dx: i64,
+    dy: i64,
+}
+
+fn signed<'a>() -> impl Parser<'a, i64> + Copy {
+    just('-')
+        .or_not()
+        .then(digits(10))
+        .to_slice()
+        .from_str::<i64>()
+        .unwrapped()
+        .padded()
+}
+
+fn pair<'a>() -> impl Parser<'a, (i64, i64)> {
+    signed()
+        .then_ignore(just(','))
+        .then(signed())
+        .delimited_by(just('<'), just('>'))
+}
+
+impl Star {
+    fn parser<'a>() -> impl Parser<'a, Star> {
+        just("position=")
+            .ignore_then(pair())
+            .then_ignore(just(" velocity="))
+            .then(pair())
+            .map(|((x, y), (dx, dy))| Star { x, y, dx, dy })
+    }
+
+    fn parse(line: &str) -> Result<Star> {
+        let res = Star::parser()
+            .parse(line)
+            .into_result()
+            .map_err(|e| eyre!("Failed to parse: {e:?}"))?;
+
+        Ok(res)
+    }
+
+    fn at(self, time: i64) -> (i64, i64) {
+        (self.x + self.dx * time, self.y + self.dy * time)
+    }
+}
+
+fn parse(input: &str) -> Result<Vec<Star>> {
+    let stars: Vec<_> = input
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(Star::parse)
+        .collect::<Result<_>>()?;
+
+    if stars.is_empty() {
+        return Err(eyre!("no stars in input"));
+    }
+
+    Ok(stars)
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bounds {
+    min_x: i64,
+    max_x: i64,
+    min_y: i64,
+    max_y: i64,
+}
+
+impl Bounds {
+    fn at(stars: &[Star], time: i64) -> Bounds {
+        stars.iter().map(|s| s.at(time)).fold(
+            Bounds {
+                min_x: i64::MAX,
+                max_x: i64::MIN,
+                min_y: i64::MAX,
+                max_y: i64::MIN,
+            },
+            |b, (x, y)| Bounds {
+                min_x: b.min_x.min(x),
+                max_x: b.max_x.max(x),
+                min_y: b.min_y.min(y),
+                max_y: b.max_y.max(y),
+            },
+        )
+    }
+
+    fn extent(&self) -> i64 {
+        (self.max_x - self.min_x) + (self.max_y - self.min_y)
+    }
+}
+
+/// Finds the second at which the stars are most tightly packed.
+///
+/// Each side of the bounding box is the max minus the min of a set of linear functions of time,
+/// which makes the box's extent convex. That lets us search for the minimum instead of stepping.
+fn converge_time(stars: &[Star]) -> i64 {
+    let extent = |t| Bounds::at(stars, t).extent();
+    let is_rising = |t| extent(t + 1) >= extent(t);
+
+    let mut hi = 1;
+    while !is_rising(hi) {
+        hi *= 2;
+    }
+
+    let mut lo = 0;
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if is_rising(mid) {
+            hi = mid;
+        } else {
+            lo = mid + 1;
+        }
+    }
+
+    lo
+}
+
+fn render(stars: &[Star], time: i64) -> String {
+    let bounds = Bounds::at(stars, time);
+    let width = (bounds.max_x - bounds.min_x + 1) as usize;
+    let height = (bounds.max_y - bounds.min_y + 1) as usize;
+
+    let mut grid = vec![vec!['.'; width]; height];
+    for (x, y) in stars.iter().map(|s| s.at(time)) {
+        grid[(y - bounds.min_y) as usize][(x - bounds.min_x) as usize] = '#';
+    }
+
+    let mut output = String::with_capacity((width + 1) * height);
+    for row in grid {
+        output.push('\n');
+        output.extend(row);
+    }
+
+    output
+}
+
+fn part1(stars: &[Star]) -> String {
+    render(stars, converge_time(stars))
+}
+
+fn part2(stars: &[Star]) -> i64 {
+    converge_time(stars)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_lib::Example;
+
+    #[test]
+    fn part1_test() {
+        let data = aoc_lib::input(DAY.day)
+            .example(Example::Part1, 1)
+            .open()
+            .unwrap();
+
+        let parsed = parse(&data).unwrap();
+        let expected = "
+#...#..###
+#...#...#.
+#...#...#.
+#####...#.
+#...#...#.
+#...#...#.
+#...#...#.
+#...#..###";
+        let actual = part1(&parsed);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn part2_test() {
+        let data = aoc_lib::input(DAY.day)
+            .example(Example::Part1, 1)
+            .open()
+            .unwrap();
+
+        let parsed = parse(&data).unwrap();
+        let expected = 3;
+        let actual = part2(&parsed);
+
+        assert_eq!(expected, actual);
+    }
+}
@@ -0,0 +1,137 @@
+use aoc_lib::{Bench, BenchResult, Day, NoError, ParseResult, UserError};
+use color_eyre::{Report, Result};
+
+pub const DAY: Day = Day {
+    day: 11,
+    name: "Chronal Charge",
+    part_1: run_part1,
+    part_2: Some(run_part2),
+    other: &[("Parse", run_parse)],
+};
+
+fn run_part1(input: &str, b: Bench) -> BenchResult {
+    let data = parse(input).map_err(UserError)?;
+    b.bench(|| Ok::<_, NoError>(part1(data)))
+}
+
+fn run_part2(input: &str, b: Bench) -> BenchResult {
+    let data = parse(input).map_err(UserError)?;
+    b.bench(|| Ok::<_, NoError>(part2(data)))
+}
+
+fn run_parse(input: &str, b: Bench) -> BenchResult {
+    b.bench(|| {
+        let data = parse(input).map_err(UserError)?;
+        Ok::<_, Report>(ParseResult(data))
+    })
+}
+
+const GRID_SIZE: usize = 300;
+
+fn parse(input: &str) -> Result<i32> {
+    Ok(input.trim().parse()?)
+}
+
+fn power_level(serial: i32, x: i32, y: i32) -> i32 {
+    let rack_id = x + 10;
+    let power = (rack_id * y + serial) * rack_id;
+    (power / 100) % 10 - 5
+}
+
+/// Summed-area table over the fuel grid, with a zeroed row and column so lookups don't need to
+/// special-case the top and left edges.
+struct PowerGrid {
+    sums: Vec<i32>,
+}
+
+impl PowerGrid {
+    const STRIDE: usize = GRID_SIZE + 1;
+
+    fn new(serial: i32) -> Self {
+        let mut sums = vec![0; Self::STRIDE * Self::STRIDE];
+
+        for y in 1..=GRID_SIZE {
+            for x in 1..=GRID_SIZE {
+                let power = power_level(serial, x as i32, y as i32);
+                sums[y * Self::STRIDE + x] =
+                    power + sums[(y - 1) * Self::STRIDE + x] + sums[y * Self::STRIDE + x - 1]
+                        - sums[(y - 1) * Self::STRIDE + x - 1];
+            }
+        }
+
+        Self { sums }
+    }
+
+    /// Total power of the square whose top-left cell is `(x, y)`, using 1-based coordinates.
+    fn square(&self, x: usize, y: usize, size: usize) -> i32 {
+        let (x0, y0) = (x - 1, y - 1);
+        let (x1, y1) = (x0 + size, y0 + size);
+
+        self.sums[y1 * Self::STRIDE + x1]
+            - self.sums[y0 * Self::STRIDE + x1]
+            - self.sums[y1 * Self::STRIDE + x0]
+            + self.sums[y0 * Self::STRIDE + x0]
+    }
+
+    fn best_of_size(&self, size: usize) -> (i32, usize, usize) {
+        let last = GRID_SIZE - size + 1;
+
+        (1..=last)
+            .flat_map(|y| (1..=last).map(move |x| (x, y)))
+            .map(|(x, y)| (self.square(x, y, size), x, y))
+            .max_by_key(|&(power, x, y)| (power, std::cmp::Reverse((y, x))))
+            .unwrap()
+    }
+}
+
+fn part1(serial: i32) -> String {
+    let grid = PowerGrid::new(serial);
+    let (_, x, y) = grid.best_of_size(3);
+    format!("{x},{y}")
+}
+
+fn part2(serial: i32) -> String {
+    let grid = PowerGrid::new(serial);
+    let (_, x, y, size) = (1..=GRID_SIZE)
+        .map(|size| {
+            let (power, x, y) = grid.best_of_size(size);
+            (power, x, y, size)
+        })
+        .max_by_key(|&(power, _, _, size)| (power, std::cmp::Reverse(size)))
+        .unwrap();
+
+    format!("{x},{y},{size}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn power_level_test() {
+        assert_eq!(power_level(8, 3, 5), 4);
+        assert_eq!(power_level(57, 122, 79), -5);
+        assert_eq!(power_level(39, 217, 196), 0);
+        assert_eq!(power_level(71, 101, 153), 4);
+    }
+
+    #[test]
+    fn part1_test() {
+        for (serial, expected) in [(18, "33,45"), (42, "21,61")] {
+            let parsed = parse(&serial.to_string()).unwrap();
+            let actual = part1(parsed);
+
+            assert_eq!(expected, actual);
+        }
+    }
+
+    #[test]
+    fn part2_test() {
+        for (serial, expected) in [(18, "90,269,16"), (42, "232,251,12")] {
+            let parsed = parse(&serial.to_string()).unwrap();
+            let actual = part2(parsed);
+
+            assert_eq!(expected, actual);
+        }
+    }
+}
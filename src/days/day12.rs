@@ -0,0 +1,194 @@
+use aoc_lib::{Bench, BenchResult, Day, NoError, ParseResult, UserError};
+use color_eyre::{eyre::eyre, Report, Result};
+
+pub const DAY: Day = Day {
+    day: 12,
+    name: "Subterranean Sustainability",
+    part_1: run_part1,
+    part_2: Some(run_part2),
+    other: &[("Parse", run_parse)],
+};
+
+fn run_part1(input: &str, b: Bench) -> BenchResult {
+    let data = parse(input).map_err(UserError)?;
+    b.bench(|| Ok::<_, NoError>(part1(&data)))
+}
+
+fn run_part2(input: &str, b: Bench) -> BenchResult {
+    let data = parse(input).map_err(UserError)?;
+    b.bench(|| Ok::<_, NoError>(part2(&data)))
+}
+
+fn run_parse(input: &str, b: Bench) -> BenchResult {
+    b.bench(|| {
+        let data = parse(input).map_err(UserError)?;
+        Ok::<_, Report>(ParseResult(data))
+    })
+}
+
+fn parse_pots(pots: &str) -> Result<Vec<bool>> {
+    pots.bytes()
+        .map(|b| match b {
+            b'#' => Ok(true),
+            b'.' => Ok(false),
+            _ => Err(eyre!("invalid pot: `{}`", b as char)),
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Pots {
+    /// Always starts and ends with a plant, unless there are no plants at all.
+    plants: Vec<bool>,
+    /// Pot number of the first entry in `plants`.
+    offset: i64,
+}
+
+impl Pots {
+    fn new(mut plants: Vec<bool>, mut offset: i64) -> Self {
+        match plants.iter().position(|&p| p) {
+            Some(first) => {
+                let last = plants.iter().rposition(|&p| p).unwrap();
+                plants.truncate(last + 1);
+                plants.drain(..first);
+                offset += first as i64;
+            }
+            None => plants.clear(),
+        }
+
+        Self { plants, offset }
+    }
+
+    fn step(&self, rules: &[bool; 32]) -> Pots {
+        // Each pot looks two either side, so the row can grow by two pots at each end.
+        let mut window = 0;
+        let mut next = Vec::with_capacity(self.plants.len() + 4);
+        let padded = self.plants.iter().copied().chain([false; 4]);
+
+        for plant in padded {
+            window = ((window << 1) | plant as usize) & 0b11111;
+            next.push(rules[window]);
+        }
+
+        Pots::new(next, self.offset - 2)
+    }
+
+    fn sum(&self) -> i64 {
+        self.plants
+            .iter()
+            .zip(self.offset..)
+            .filter(|(&p, _)| p)
+            .map(|(_, i)| i)
+            .sum()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Garden {
+    initial: Pots,
+    rules: [bool; 32],
+}
+
+impl Garden {
+    /// Runs the automaton for the given number of generations.
+    ///
+    /// Once a generation is the same pattern as the last, just shifted, every later generation
+    /// will only keep shifting, so the rest of the run is extrapolated from there.
+    fn simulate(&self, generations: u64) -> Pots {
+        let mut cur = self.initial.clone();
+
+        for generation in 0..generations {
+            let mut next = cur.step(&self.rules);
+            if next.plants == cur.plants {
+                let shift = next.offset - cur.offset;
+                let remaining = (generations - generation - 1) as i64;
+                next.offset += shift * remaining;
+                return next;
+            }
+            cur = next;
+        }
+
+        cur
+    }
+}
+
+fn parse(input: &str) -> Result<Garden> {
+    let mut lines = input.lines().map(str::trim);
+
+    let initial = lines
+        .next()
+        .and_then(|l| l.strip_prefix("initial state: "))
+        .ok_or_else(|| eyre!("missing initial state"))?;
+    let initial = Pots::new(parse_pots(initial)?, 0);
+
+    let mut rules = [false; 32];
+    for line in lines.filter(|l| !l.is_empty()) {
+        let (pattern, result) = line
+            .split_once(" => ")
+            .ok_or_else(|| eyre!("invalid rule: `{line}`"))?;
+
+        let pattern = parse_pots(pattern)?;
+        let [result] = parse_pots(result)?[..] else {
+            return Err(eyre!("invalid rule result: `{line}`"));
+        };
+        if pattern.len() != 5 {
+            return Err(eyre!("invalid rule pattern: `{line}`"));
+        }
+
+        let idx = pattern.iter().fold(0, |acc, &p| (acc << 1) | p as usize);
+        rules[idx] = result;
+    }
+
+    if rules[0] {
+        return Err(eyre!(
+            "`..... => #` would fill the infinite row with plants"
+        ));
+    }
+
+    Ok(Garden { initial, rules })
+}
+
+fn part1(garden: &Garden) -> i64 {
+    garden.simulate(20).sum()
+}
+
+fn part2(garden: &Garden) -> i64 {
+    garden.simulate(50_000_000_000).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_lib::Example;
+
+    #[test]
+    fn part1_test() {
+        let data = aoc_lib::input(DAY.day)
+            .example(Example::Part1, 1)
+            .open()
+            .unwrap();
+
+        let parsed = parse(&data).unwrap();
+        let expected = 325;
+        let actual = part1(&parsed);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn extrapolate_test() {
+        let data = aoc_lib::input(DAY.day)
+            .example(Example::Part1, 1)
+            .open()
+            .unwrap();
+
+        let parsed = parse(&data).unwrap();
+        let mut expected = parsed.initial.clone();
+        for _ in 0..1000 {
+            expected = expected.step(&parsed.rules);
+        }
+        let actual = parsed.simulate(1000);
+
+        assert_eq!(expected, actual);
+    }
+}
@@ -0,0 +1,372 @@
+use aoc_lib::{Bench, BenchResult, Day, ParseResult, UserError};
+use color_eyre::{eyre::eyre, Report, Result};
+
+pub const DAY: Day = Day {
+    day: 13,
+    name: "Mine Cart Madness",
+    part_1: run_part1,
+    part_2: Some(run_part2),
+    other: &[("Parse", run_parse)],
+};
+
+fn run_part1(input: &str, b: Bench) -> BenchResult {
+    let data = parse(input).map_err(UserError)?;
+    b.bench(|| part1(&data))
+}
+
+fn run_part2(input: &str, b: Bench) -> BenchResult {
+    let data = parse(input).map_err(UserError)?;
+    b.bench(|| part2(&data))
+}
+
+fn run_parse(input: &str, b: Bench) -> BenchResult {
+    b.bench(|| {
+        let data = parse(input).map_err(UserError)?;
+        Ok::<_, Report>(ParseResult(data))
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Track {
+    Empty,
+    Vertical,
+    Horizontal,
+    /// `/`
+    CurveForward,
+    /// `\`
+    CurveBack,
+    Intersection,
+}
+
+impl Track {
+    fn connects_vertically(self) -> bool {
+        !matches!(self, Track::Empty | Track::Horizontal)
+    }
+
+    fn connects_horizontally(self) -> bool {
+        !matches!(self, Track::Empty | Track::Vertical)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    fn turn_left(self) -> Direction {
+        match self {
+            Direction::Up => Direction::Left,
+            Direction::Right => Direction::Up,
+            Direction::Down => Direction::Right,
+            Direction::Left => Direction::Down,
+        }
+    }
+
+    fn turn_right(self) -> Direction {
+        match self {
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Up,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Turn {
+    Left,
+    Straight,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Cart {
+    x: usize,
+    y: usize,
+    direction: Direction,
+    next_turn: Turn,
+    crashed: bool,
+}
+
+impl Cart {
+    /// The position the cart is heading for, or `None` if that's off the top or left edge.
+    fn next_position(&self) -> Option<(usize, usize)> {
+        match self.direction {
+            Direction::Up => Some((self.x, self.y.checked_sub(1)?)),
+            Direction::Right => Some((self.x + 1, self.y)),
+            Direction::Down => Some((self.x, self.y + 1)),
+            Direction::Left => Some((self.x.checked_sub(1)?, self.y)),
+        }
+    }
+
+    /// Whether the cart can move onto `track` in its current direction.
+    fn can_enter(&self, track: Track) -> bool {
+        match self.direction {
+            Direction::Up | Direction::Down => track.connects_vertically(),
+            Direction::Left | Direction::Right => track.connects_horizontally(),
+        }
+    }
+
+    /// Moves the cart to `(x, y)`, which must be its next position, and onto `track`.
+    fn advance(&mut self, (x, y): (usize, usize), track: Track) {
+        (self.x, self.y) = (x, y);
+
+        self.direction = match (track, self.direction) {
+            (Track::CurveForward, Direction::Up | Direction::Down)
+            | (Track::CurveBack, Direction::Left | Direction::Right) => self.direction.turn_right(),
+            (Track::CurveForward, Direction::Left | Direction::Right)
+            | (Track::CurveBack, Direction::Up | Direction::Down) => self.direction.turn_left(),
+            (Track::Intersection, dir) => {
+                let (new_dir, next_turn) = match self.next_turn {
+                    Turn::Left => (dir.turn_left(), Turn::Straight),
+                    Turn::Straight => (dir, Turn::Right),
+                    Turn::Right => (dir.turn_right(), Turn::Left),
+                };
+                self.next_turn = next_turn;
+                new_dir
+            }
+            (_, dir) => dir,
+        };
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Mine {
+    tracks: Vec<Track>,
+    width: usize,
+    carts: Vec<Cart>,
+}
+
+impl Mine {
+    fn track(&self, x: usize, y: usize) -> Track {
+        if x >= self.width {
+            return Track::Empty;
+        }
+
+        self.tracks
+            .get(y * self.width + x)
+            .copied()
+            .unwrap_or(Track::Empty)
+    }
+
+    /// Moves every cart once in reading order, returning the locations of any crashes.
+    ///
+    /// Crashed carts are marked, but left in place so that indices stay valid mid-tick.
+    fn tick(&mut self) -> Result<Vec<(usize, usize)>> {
+        let mut crashes = Vec::new();
+        self.carts.sort_unstable_by_key(|c| (c.y, c.x));
+
+        for i in 0..self.carts.len() {
+            if self.carts[i].crashed {
+                continue;
+            }
+
+            let cart = &self.carts[i];
+            let next = cart.next_position();
+            let track = next.map_or(Track::Empty, |(x, y)| self.track(x, y));
+            let (Some((x, y)), true) = (next, cart.can_enter(track)) else {
+                return Err(eyre!("cart at {},{} ran off the track", cart.x, cart.y));
+            };
+            self.carts[i].advance((x, y), track);
+
+            let other = self
+                .carts
+                .iter()
+                .enumerate()
+                .position(|(j, c)| j != i && !c.crashed && c.x == x && c.y == y);
+
+            if let Some(j) = other {
+                self.carts[i].crashed = true;
+                self.carts[j].crashed = true;
+                crashes.push((x, y));
+            }
+        }
+
+        Ok(crashes)
+    }
+
+    /// Ticks until at least one crash happens, returning the locations of the crashes.
+    ///
+    /// Carts only affect each other by crashing, and each cart's path can be run backwards, so
+    /// carts that never crash must come back to the state they started in.
+    fn tick_until_crash(&mut self) -> Result<Vec<(usize, usize)>> {
+        self.carts.sort_unstable_by_key(|c| (c.y, c.x));
+        let start = self.carts.clone();
+
+        loop {
+            let crashes = self.tick()?;
+            if !crashes.is_empty() {
+                return Ok(crashes);
+            }
+
+            self.carts.sort_unstable_by_key(|c| (c.y, c.x));
+            if self.carts == start {
+                return Err(eyre!("the carts never crash"));
+            }
+        }
+    }
+}
+
+fn parse(input: &str) -> Result<Mine> {
+    let lines: Vec<_> = input
+        .lines()
+        .map(|l| l.trim_end_matches('\r'))
+        .filter(|l| !l.trim().is_empty())
+        .collect();
+    let width = lines.iter().map(|l| l.len()).max().unwrap_or(0);
+
+    let mut tracks = vec![Track::Empty; width * lines.len()];
+    let mut carts = Vec::new();
+
+    for (y, line) in lines.iter().enumerate() {
+        for (x, b) in line.bytes().enumerate() {
+            let track = match b {
+                b' ' => Track::Empty,
+                b'|' | b'^' | b'v' => Track::Vertical,
+                b'-' | b'<' | b'>' => Track::Horizontal,
+                b'/' => Track::CurveForward,
+                b'\\' => Track::CurveBack,
+                b'+' => Track::Intersection,
+                _ => return Err(eyre!("invalid track `{}` at {x},{y}", b as char)),
+            };
+            tracks[y * width + x] = track;
+
+            let direction = match b {
+                b'^' => Direction::Up,
+                b'v' => Direction::Down,
+                b'<' => Direction::Left,
+                b'>' => Direction::Right,
+                _ => continue,
+            };
+            carts.push(Cart {
+                x,
+                y,
+                direction,
+                next_turn: Turn::Left,
+                crashed: false,
+            });
+        }
+    }
+
+    let track_at = |x: Option<usize>, y: Option<usize>| match (x, y) {
+        (Some(x), Some(y)) if x < width && y < lines.len() => tracks[y * width + x],
+        _ => Track::Empty,
+    };
+
+    for cart in &carts {
+        let (x, y) = (cart.x, cart.y);
+        let on_track = match cart.direction {
+            Direction::Up | Direction::Down => {
+                track_at(Some(x), y.checked_sub(1)).connects_vertically()
+                    && track_at(Some(x), Some(y + 1)).connects_vertically()
+            }
+            Direction::Left | Direction::Right => {
+                track_at(x.checked_sub(1), Some(y)).connects_horizontally()
+                    && track_at(Some(x + 1), Some(y)).connects_horizontally()
+            }
+        };
+
+        if !on_track {
+            return Err(eyre!("cart at {x},{y} is not on a track"));
+        }
+    }
+
+    Ok(Mine {
+        tracks,
+        width,
+        carts,
+    })
+}
+
+fn part1(mine: &Mine) -> Result<String> {
+    let mut mine = mine.clone();
+    if mine.carts.len() < 2 {
+        return Err(eyre!("need at least two carts to crash"));
+    }
+
+    let (x, y) = mine.tick_until_crash()?[0];
+    Ok(format!("{x},{y}"))
+}
+
+fn part2(mine: &Mine) -> Result<String> {
+    let mut mine = mine.clone();
+
+    while mine.carts.len() > 1 {
+        mine.tick_until_crash()?;
+        mine.carts.retain(|c| !c.crashed);
+    }
+
+    match mine.carts[..] {
+        [Cart { x, y, .. }] => Ok(format!("{x},{y}")),
+        _ => Err(eyre!("no carts survived")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_lib::Example;
+
+    #[test]
+    fn part1_test() {
+        let data = aoc_lib::input(DAY.day)
+            .example(Example::Part1, 1)
+            .open()
+            .unwrap();
+
+        let parsed = parse(&data).unwrap();
+        let expected = "7,3";
+        let actual = part1(&parsed).unwrap();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn part2_test() {
+        let data = aoc_lib::input(DAY.day)
+            .example(Example::Part2, 1)
+            .open()
+            .unwrap();
+
+        let parsed = parse(&data).unwrap();
+        let expected = "6,4";
+        let actual = part2(&parsed).unwrap();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn off_track_test() {
+        assert!(parse("/-\\\n|>|\n\\-/").is_err());
+        assert!(parse("/-\\\n| |\n\\-/\n >").is_err());
+        assert!(parse("/->-\\\n|   |\n\\---/").is_ok());
+    }
+
+    #[test]
+    fn never_crash_test() {
+        let chase = "/->->-\\\n|     |\n\\-----/";
+        let parsed = parse(chase).unwrap();
+        assert!(part1(&parsed).is_err());
+        assert!(part2(&parsed).is_err());
+
+        // The bottom two crash head on, leaving the top two chasing each other.
+        let survivors = "/->->-\\\n|     |\n\\->-<-/";
+        let parsed = parse(survivors).unwrap();
+        assert_eq!("3,2", part1(&parsed).unwrap());
+        assert!(part2(&parsed).is_err());
+    }
+
+    #[test]
+    fn dead_end_test() {
+        // Off the right edge, off the left edge, and into a track going the wrong way.
+        for input in ["->->-", "-<-<-", "->-|-<-"] {
+            let parsed = parse(input).unwrap();
+            let err = part1(&parsed).unwrap_err().to_string();
+            assert!(err.contains("ran off the track"), "{input}: {err}");
+        }
+    }
+}
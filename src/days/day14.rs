@@ -0,0 +1,160 @@
+use aoc_lib::{Bench, BenchResult, Day, NoError, ParseResult, UserError};
+use color_eyre::{eyre::eyre, Report, Result};
+
+pub const DAY: Day = Day {
+    day: 14,
+    name: "Chocolate Charts",
+    part_1: run_part1,
+    part_2: Some(run_part2),
+    other: &[("Parse", run_parse)],
+};
+
+fn run_part1(input: &str, b: Bench) -> BenchResult {
+    let data = parse(input).map_err(UserError)?;
+    b.bench(|| Ok::<_, NoError>(part1(&data)))
+}
+
+fn run_part2(input: &str, b: Bench) -> BenchResult {
+    let data = parse(input).map_err(UserError)?;
+    b.bench(|| Ok::<_, NoError>(part2(&data)))
+}
+
+fn run_parse(input: &str, b: Bench) -> BenchResult {
+    b.bench(|| {
+        let data = parse(input).map_err(UserError)?;
+        Ok::<_, Report>(ParseResult(data))
+    })
+}
+
+#[derive(Debug, Clone)]
+struct Puzzle {
+    /// The input read as a number of recipes, for part 1.
+    recipes: usize,
+    /// The input read as a sequence of scores, for part 2.
+    sequence: Vec<u8>,
+}
+
+fn parse(input: &str) -> Result<Puzzle> {
+    let input = input.trim();
+    let sequence = input
+        .bytes()
+        .map(|b| match b {
+            b'0'..=b'9' => Ok(b - b'0'),
+            _ => Err(eyre!("invalid digit: `{}`", b as char)),
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    if sequence.is_empty() {
+        return Err(eyre!("empty input"));
+    }
+
+    Ok(Puzzle {
+        recipes: input.parse()?,
+        sequence,
+    })
+}
+
+#[derive(Debug)]
+struct Scoreboard {
+    scores: Vec<u8>,
+    elves: [usize; 2],
+}
+
+impl Scoreboard {
+    fn new() -> Self {
+        Self {
+            scores: vec![3, 7],
+            elves: [0, 1],
+        }
+    }
+
+    /// Combines the elves' current recipes, and calls `on_push` after each new score is added.
+    ///
+    /// Returns early with `true` if `on_push` does.
+    fn step(&mut self, mut on_push: impl FnMut(&[u8]) -> bool) -> bool {
+        let [a, b] = self.elves;
+        let sum = self.scores[a] + self.scores[b];
+
+        if sum >= 10 {
+            self.scores.push(sum / 10);
+            if on_push(&self.scores) {
+                return true;
+            }
+        }
+        self.scores.push(sum % 10);
+        if on_push(&self.scores) {
+            return true;
+        }
+
+        let len = self.scores.len();
+        self.elves = [
+            (a + 1 + self.scores[a] as usize) % len,
+            (b + 1 + self.scores[b] as usize) % len,
+        ];
+
+        false
+    }
+}
+
+fn part1(puzzle: &Puzzle) -> String {
+    let mut board = Scoreboard::new();
+    board.scores.reserve(puzzle.recipes + 11);
+
+    while board.scores.len() < puzzle.recipes + 10 {
+        board.step(|_| false);
+    }
+
+    board.scores[puzzle.recipes..puzzle.recipes + 10]
+        .iter()
+        .map(|&s| (s + b'0') as char)
+        .collect()
+}
+
+fn part2(puzzle: &Puzzle) -> usize {
+    let mut board = Scoreboard::new();
+    let needle = &puzzle.sequence[..];
+
+    // Every new score only completes matches that end on it, so we only check the tail.
+    let ends_with_needle = |scores: &[u8]| scores.ends_with(needle);
+    if ends_with_needle(&board.scores) {
+        return board.scores.len() - needle.len();
+    }
+
+    while !board.step(ends_with_needle) {}
+
+    board.scores.len() - needle.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part1_test() {
+        let tests = [
+            ("9", "5158916779"),
+            ("5", "0124515891"),
+            ("18", "9251071085"),
+            ("2018", "5941429882"),
+        ];
+
+        for (input, expected) in tests {
+            let parsed = parse(input).unwrap();
+            let actual = part1(&parsed);
+
+            assert_eq!(expected, actual);
+        }
+    }
+
+    #[test]
+    fn part2_test() {
+        let tests = [("51589", 9), ("01245", 5), ("92510", 18), ("59414", 2018)];
+
+        for (input, expected) in tests {
+            let parsed = parse(input).unwrap();
+            let actual = part2(&parsed);
+
+            assert_eq!(expected, actual);
+        }
+    }
+}
@@ -0,0 +1,475 @@
+use std::{collections::VecDeque, fmt::Display};
+
+use aoc_lib::{Bench, BenchResult, Day, NoError, ParseResult, UserError};
+use color_eyre::{eyre::eyre, Report, Result};
+
+pub const DAY: Day = Day {
+    day: 15,
+    name: "Beverage Bandits",
+    part_1: run_part1,
+    part_2: Some(run_part2),
+    other: &[("Parse", run_parse)],
+};
+
+fn run_part1(input: &str, b: Bench) -> BenchResult {
+    let data = parse(input).map_err(UserError)?;
+    b.bench(|| Ok::<_, NoError>(part1(&data).score()))
+}
+
+fn run_part2(input: &str, b: Bench) -> BenchResult {
+    let data = parse(input).map_err(UserError)?;
+    b.bench(|| {
+        let (_, outcome) = part2(&data)?;
+        Ok::<_, Report>(outcome.score())
+    })
+}
+
+fn run_parse(input: &str, b: Bench) -> BenchResult {
+    b.bench(|| {
+        let data = parse(input).map_err(UserError)?;
+        Ok::<_, Report>(ParseResult(data))
+    })
+}
+
+const START_HP: u16 = 200;
+const DEFAULT_ATTACK: u16 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Elf,
+    Goblin,
+}
+
+impl Kind {
+    fn symbol(self) -> char {
+        match self {
+            Kind::Elf => 'E',
+            Kind::Goblin => 'G',
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Unit {
+    kind: Kind,
+    /// Index into the map, so sorting by position gives reading order.
+    pos: usize,
+    hp: u16,
+    attack: u16,
+}
+
+impl Unit {
+    fn is_alive(&self) -> bool {
+        self.hp > 0
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Cave {
+    open: Vec<bool>,
+    width: usize,
+    units: Vec<Unit>,
+}
+
+fn parse(input: &str) -> Result<Cave> {
+    let lines: Vec<_> = input
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect();
+    let width = lines.first().map(|l| l.len()).unwrap_or(0);
+    let height = lines.len();
+
+    let mut open = Vec::with_capacity(width * height);
+    let mut units = Vec::new();
+
+    for (y, line) in lines.iter().enumerate() {
+        if line.len() != width {
+            return Err(eyre!("line {} has a different width to the first", y + 1));
+        }
+
+        for (x, b) in line.bytes().enumerate() {
+            let pos = y * width + x;
+            let is_open = match b {
+                b'#' => false,
+                b'.' => true,
+                b'E' | b'G' => {
+                    let kind = if b == b'E' { Kind::Elf } else { Kind::Goblin };
+                    units.push(Unit {
+                        kind,
+                        pos,
+                        hp: START_HP,
+                        attack: DEFAULT_ATTACK,
+                    });
+                    true
+                }
+                _ => return Err(eyre!("invalid tile `{}` at {x},{y}", b as char)),
+            };
+
+            // Keeping the edge solid means we never need to bounds check a neighbour.
+            let is_edge = x == 0 || y == 0 || x == width - 1 || y == height - 1;
+            if is_open && is_edge {
+                return Err(eyre!("open tile at {x},{y} on the edge of the map"));
+            }
+
+            open.push(is_open);
+        }
+    }
+
+    Ok(Cave { open, width, units })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Outcome {
+    winner: Kind,
+    rounds: u32,
+    hit_points: u32,
+}
+
+impl Outcome {
+    fn score(&self) -> u32 {
+        self.rounds * self.hit_points
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Battle {
+    open: Vec<bool>,
+    width: usize,
+    units: Vec<Unit>,
+    /// Which unit, if any, is standing on each tile.
+    occupant: Vec<Option<usize>>,
+    rounds: u32,
+    elf_died: bool,
+}
+
+impl Battle {
+    fn new(cave: &Cave, elf_attack: u16) -> Self {
+        let mut units = cave.units.clone();
+        let mut occupant = vec![None; cave.open.len()];
+
+        for (id, unit) in units.iter_mut().enumerate() {
+            if unit.kind == Kind::Elf {
+                unit.attack = elf_attack;
+            }
+            occupant[unit.pos] = Some(id);
+        }
+
+        Self {
+            open: cave.open.clone(),
+            width: cave.width,
+            units,
+            occupant,
+            rounds: 0,
+            elf_died: false,
+        }
+    }
+
+    /// Neighbouring tiles, in reading order.
+    fn neighbours(&self, pos: usize) -> [usize; 4] {
+        [pos - self.width, pos - 1, pos + 1, pos + self.width]
+    }
+
+    fn is_free(&self, pos: usize) -> bool {
+        self.open[pos] && self.occupant[pos].is_none()
+    }
+
+    fn enemies_remain(&self, kind: Kind) -> bool {
+        self.units.iter().any(|u| u.is_alive() && u.kind != kind)
+    }
+
+    /// Steps from `start` to every free tile reachable from it.
+    fn distances(&self, start: usize) -> Vec<u32> {
+        let mut dists = vec![u32::MAX; self.open.len()];
+        let mut queue = VecDeque::from([start]);
+        dists[start] = 0;
+
+        while let Some(pos) = queue.pop_front() {
+            for next in self.neighbours(pos) {
+                if self.is_free(next) && dists[next] == u32::MAX {
+                    dists[next] = dists[pos] + 1;
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        dists
+    }
+
+    fn adjacent_enemy(&self, id: usize) -> Option<usize> {
+        let unit = self.units[id];
+
+        self.neighbours(unit.pos)
+            .into_iter()
+            .filter_map(|n| self.occupant[n])
+            .filter(|&o| self.units[o].kind != unit.kind)
+            .min_by_key(|&o| (self.units[o].hp, self.units[o].pos))
+    }
+
+    fn move_unit(&mut self, id: usize) {
+        if self.adjacent_enemy(id).is_some() {
+            return;
+        }
+
+        let unit = self.units[id];
+        let mut in_range = vec![false; self.open.len()];
+        for enemy in self
+            .units
+            .iter()
+            .filter(|u| u.is_alive() && u.kind != unit.kind)
+        {
+            for n in self.neighbours(enemy.pos) {
+                in_range[n] |= self.is_free(n);
+            }
+        }
+
+        let dists = self.distances(unit.pos);
+        let target = (0..in_range.len())
+            .filter(|&p| in_range[p] && dists[p] != u32::MAX)
+            .min_by_key(|&p| (dists[p], p));
+        let Some(target) = target else {
+            return;
+        };
+
+        // Measuring back from the target lets us pick the first step that lies on a shortest path.
+        let back = self.distances(target);
+        let step = self
+            .neighbours(unit.pos)
+            .into_iter()
+            .filter(|&n| self.is_free(n) && back[n] != u32::MAX)
+            .min_by_key(|&n| (back[n], n))
+            .unwrap();
+
+        self.occupant[unit.pos] = None;
+        self.occupant[step] = Some(id);
+        self.units[id].pos = step;
+    }
+
+    fn attack(&mut self, id: usize) {
+        let Some(target_id) = self.adjacent_enemy(id) else {
+            return;
+        };
+
+        let attack = self.units[id].attack;
+        let target = &mut self.units[target_id];
+        target.hp = target.hp.saturating_sub(attack);
+
+        if !target.is_alive() {
+            self.occupant[target.pos] = None;
+            self.elf_died |= target.kind == Kind::Elf;
+        }
+    }
+
+    /// Plays out one round of combat.
+    ///
+    /// Returns `false` if a unit found no enemies left during its turn, in which case the round
+    /// doesn't count as completed.
+    fn round(&mut self) -> bool {
+        let mut order: Vec<_> = (0..self.units.len())
+            .filter(|&id| self.units[id].is_alive())
+            .collect();
+        order.sort_unstable_by_key(|&id| self.units[id].pos);
+
+        for id in order {
+            let unit = self.units[id];
+            if !unit.is_alive() {
+                continue;
+            }
+            if !self.enemies_remain(unit.kind) {
+                return false;
+            }
+
+            self.move_unit(id);
+            self.attack(id);
+        }
+
+        self.rounds += 1;
+        true
+    }
+
+    fn outcome(&self) -> Outcome {
+        let alive = self.units.iter().filter(|u| u.is_alive());
+
+        Outcome {
+            winner: alive.clone().map(|u| u.kind).next().unwrap_or(Kind::Goblin),
+            rounds: self.rounds,
+            hit_points: alive.map(|u| u.hp as u32).sum(),
+        }
+    }
+}
+
+impl Display for Battle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (y, row) in self.open.chunks(self.width).enumerate() {
+            let mut row_units = Vec::new();
+
+            for (x, &open) in row.iter().enumerate() {
+                let c = match self.occupant[y * self.width + x] {
+                    Some(id) => {
+                        let unit = self.units[id];
+                        row_units.push(unit);
+                        unit.kind.symbol()
+                    }
+                    None if open => '.',
+                    None => '#',
+                };
+                write!(f, "{c}")?;
+            }
+
+            for (i, unit) in row_units.iter().enumerate() {
+                let sep = if i == 0 { "   " } else { ", " };
+                write!(f, "{sep}{}({})", unit.kind.symbol(), unit.hp)?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Runs a battle to completion, or returns `None` early if an elf dies when `stop_on_elf_death` is
+/// set.
+fn fight(cave: &Cave, elf_attack: u16, stop_on_elf_death: bool) -> Option<Outcome> {
+    let mut battle = Battle::new(cave, elf_attack);
+
+    loop {
+        let completed = battle.round();
+        if stop_on_elf_death && battle.elf_died {
+            return None;
+        }
+        if !completed {
+            return Some(battle.outcome());
+        }
+    }
+}
+
+fn part1(cave: &Cave) -> Outcome {
+    fight(cave, DEFAULT_ATTACK, false).unwrap()
+}
+
+fn part2(cave: &Cave) -> Result<(u16, Outcome)> {
+    // At 200 attack an elf kills anything it hits, so there's no point looking further.
+    (DEFAULT_ATTACK + 1..=START_HP)
+        .find_map(|attack| fight(cave, attack, true).map(|o| (attack, o)))
+        .ok_or_else(|| eyre!("no attack power lets every elf survive"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_lib::Example;
+
+    fn map_only(battle: &Battle) -> String {
+        battle
+            .to_string()
+            .lines()
+            .map(|l| l.split_whitespace().next().unwrap())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn movement_test() {
+        let data = aoc_lib::input(DAY.day)
+            .example(Example::Part1, 1)
+            .open()
+            .unwrap();
+
+        let parsed = parse(&data).unwrap();
+        let mut battle = Battle::new(&parsed, DEFAULT_ATTACK);
+        let expected = [
+            "#########\n#.G...G.#\n#...G...#\n#...E..G#\n#.G.....#\n#.......#\n#G..G..G#\n#.......#\n#########",
+            "#########\n#..G.G..#\n#...G...#\n#.G.E.G.#\n#.......#\n#G..G..G#\n#.......#\n#.......#\n#########",
+            "#########\n#.......#\n#..GGG..#\n#..GEG..#\n#G..G...#\n#......G#\n#.......#\n#.......#\n#########",
+        ];
+
+        for expected in expected {
+            assert!(battle.round());
+            assert_eq!(expected, map_only(&battle));
+        }
+    }
+
+    #[test]
+    fn combat_state_test() {
+        let data = aoc_lib::input(DAY.day)
+            .example(Example::Part1, 2)
+            .open()
+            .unwrap();
+
+        let parsed = parse(&data).unwrap();
+        let mut battle = Battle::new(&parsed, DEFAULT_ATTACK);
+        while battle.round() {}
+
+        let expected = "\
+#######
+#G....#   G(200)
+#.G...#   G(131)
+#.#.#G#   G(59)
+#...#.#
+#....G#   G(200)
+#######
+";
+        assert_eq!(expected, battle.to_string());
+        assert_eq!(battle.rounds, 47);
+    }
+
+    #[test]
+    fn part1_test() {
+        let tests = [
+            (2, Kind::Goblin, 47, 590, 27730),
+            (3, Kind::Elf, 37, 982, 36334),
+            (4, Kind::Elf, 46, 859, 39514),
+            (5, Kind::Goblin, 35, 793, 27755),
+            (6, Kind::Goblin, 54, 536, 28944),
+            (7, Kind::Goblin, 20, 937, 18740),
+        ];
+
+        for (example, winner, rounds, hit_points, score) in tests {
+            let data = aoc_lib::input(DAY.day)
+                .example(Example::Part1, example)
+                .open()
+                .unwrap();
+
+            let parsed = parse(&data).unwrap();
+            let expected = Outcome {
+                winner,
+                rounds,
+                hit_points,
+            };
+            let actual = part1(&parsed);
+
+            assert_eq!(expected, actual, "example {example}");
+            assert_eq!(score, actual.score(), "example {example}");
+        }
+    }
+
+    #[test]
+    fn part2_test() {
+        let tests = [
+            (2, 15, 29, 172, 4988),
+            (4, 4, 33, 948, 31284),
+            (5, 15, 37, 94, 3478),
+            (6, 12, 39, 166, 6474),
+            (7, 34, 30, 38, 1140),
+        ];
+
+        for (example, attack, rounds, hit_points, score) in tests {
+            let data = aoc_lib::input(DAY.day)
+                .example(Example::Part1, example)
+                .open()
+                .unwrap();
+
+            let parsed = parse(&data).unwrap();
+            let expected = Outcome {
+                winner: Kind::Elf,
+                rounds,
+                hit_points,
+            };
+            let (actual_attack, actual) = part2(&parsed).unwrap();
+
+            assert_eq!(attack, actual_attack, "example {example}");
+            assert_eq!(expected, actual, "example {example}");
+            assert_eq!(score, actual.score(), "example {example}");
+        }
+    }
+}
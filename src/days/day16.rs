@@ -0,0 +1,385 @@
+use aoc_lib::{Bench, BenchResult, Day, NoError, ParseResult, UserError};
+use chumsky::{primitive::just, text::digits, IterParser, Parser as _};
+use color_eyre::{eyre::eyre, Report, Result};
+
+use crate::Parser;
+
+pub const DAY: Day = Day {
+    day: 16,
+    name: "Chronal Classification",
+    part_1: run_part1,
+    part_2: Some(run_part2),
+    other: &[("Parse", run_parse)],
+};
+
+fn run_part1(input: &str, b: Bench) -> BenchResult {
+    let data = parse(input).map_err(UserError)?;
+    b.bench(|| Ok::<_, NoError>(part1(&data)))
+}
+
+fn run_part2(input: &str, b: Bench) -> BenchResult {
+    let data = parse(input).map_err(UserError)?;
+    deduce_opcodes(&data.samples).map_err(UserError)?;
+    b.bench(|| part2(&data))
+}
+
+fn run_parse(input: &str, b: Bench) -> BenchResult {
+    b.bench(|| {
+        let data = parse(input).map_err(UserError)?;
+        Ok::<_, Report>(ParseResult(data))
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Op {
+    Addr,
+    Addi,
+    Mulr,
+    Muli,
+    Banr,
+    Bani,
+    Borr,
+    Bori,
+    Setr,
+    Seti,
+    Gtir,
+    Gtri,
+    Gtrr,
+    Eqir,
+    Eqri,
+    Eqrr,
+}
+
+impl Op {
+    pub(super) const ALL: [Op; 16] = [
+        Op::Addr,
+        Op::Addi,
+        Op::Mulr,
+        Op::Muli,
+        Op::Banr,
+        Op::Bani,
+        Op::Borr,
+        Op::Bori,
+        Op::Setr,
+        Op::Seti,
+        Op::Gtir,
+        Op::Gtri,
+        Op::Gtrr,
+        Op::Eqir,
+        Op::Eqri,
+        Op::Eqrr,
+    ];
+
+    pub(super) fn from_name(name: &str) -> Option<Op> {
+        Op::ALL.into_iter().find(|op| op.name() == name)
+    }
+
+    pub(super) fn name(self) -> &'static str {
+        match self {
+            Op::Addr => "addr",
+            Op::Addi => "addi",
+            Op::Mulr => "mulr",
+            Op::Muli => "muli",
+            Op::Banr => "banr",
+            Op::Bani => "bani",
+            Op::Borr => "borr",
+            Op::Bori => "bori",
+            Op::Setr => "setr",
+            Op::Seti => "seti",
+            Op::Gtir => "gtir",
+            Op::Gtri => "gtri",
+            Op::Gtrr => "gtrr",
+            Op::Eqir => "eqir",
+            Op::Eqri => "eqri",
+            Op::Eqrr => "eqrr",
+        }
+    }
+
+    /// Runs the operation, writing the result into register `c`.
+    ///
+    /// Returns `None` if any register operand is out of range.
+    pub(super) fn execute<const N: usize>(
+        self,
+        a: usize,
+        b: usize,
+        c: usize,
+        regs: &mut [usize; N],
+    ) -> Option<()> {
+        let reg = |r: usize| regs.get(r).copied();
+
+        let value = match self {
+            Op::Addr => reg(a)? + reg(b)?,
+            Op::Addi => reg(a)? + b,
+            Op::Mulr => reg(a)? * reg(b)?,
+            Op::Muli => reg(a)? * b,
+            Op::Banr => reg(a)? & reg(b)?,
+            Op::Bani => reg(a)? & b,
+            Op::Borr => reg(a)? | reg(b)?,
+            Op::Bori => reg(a)? | b,
+            Op::Setr => reg(a)?,
+            Op::Seti => a,
+            Op::Gtir => (a > reg(b)?) as usize,
+            Op::Gtri => (reg(a)? > b) as usize,
+            Op::Gtrr => (reg(a)? > reg(b)?) as usize,
+            Op::Eqir => (a == reg(b)?) as usize,
+            Op::Eqri => (reg(a)? == b) as usize,
+            Op::Eqrr => (reg(a)? == reg(b)?) as usize,
+        };
+
+        *regs.get_mut(c)? = value;
+        Some(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Instruction {
+    opcode: usize,
+    a: usize,
+    b: usize,
+    c: usize,
+}
+
+impl Instruction {
+    fn parser<'a>() -> impl Parser<'a, Instruction> {
+        number()
+            .separated_by(just(' '))
+            .exactly(4)
+            .collect::<Vec<_>>()
+            .map(|v| Instruction {
+                opcode: v[0],
+                a: v[1],
+                b: v[2],
+                c: v[3],
+            })
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    before: [usize; 4],
+    instruction: Instruction,
+    after: [usize; 4],
+}
+
+impl Sample {
+    fn parser<'a>() -> impl Parser<'a, Sample> {
+        let registers = number()
+            .padded()
+            .separated_by(just(','))
+            .exactly(4)
+            .collect::<Vec<_>>()
+            .map(|v| [v[0], v[1], v[2], v[3]])
+            .delimited_by(just('['), just(']'));
+
+        just("Before:")
+            .ignore_then(registers.padded())
+            .then(Instruction::parser())
+            .then_ignore(just("After:").padded())
+            .then(registers)
+            .padded()
+            .map(|((before, instruction), after)| Sample {
+                before,
+                instruction,
+                after,
+            })
+    }
+
+    /// Bitmask of the operations in [`Op::ALL`] that are consistent with this sample.
+    fn matching_ops(&self) -> u16 {
+        let Instruction { a, b, c, .. } = self.instruction;
+
+        Op::ALL
+            .iter()
+            .enumerate()
+            .filter(|(_, op)| {
+                let mut regs = self.before;
+                op.execute(a, b, c, &mut regs).is_some() && regs == self.after
+            })
+            .fold(0, |mask, (i, _)| mask | 1 << i)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Manual {
+    samples: Vec<Sample>,
+    program: Vec<Instruction>,
+}
+
+fn number<'a>() -> impl Parser<'a, usize> + Copy {
+    digits(10).to_slice().from_str::<usize>().unwrapped()
+}
+
+fn parse(input: &str) -> Result<Manual> {
+    let input = input.replace("\r\n", "\n");
+    let (samples, program) = input.split_once("\n\n\n").unwrap_or((&input, ""));
+
+    let samples = samples
+        .split("\n\n")
+        .filter(|s| !s.trim().is_empty())
+        .enumerate()
+        .map(|(i, s)| {
+            Sample::parser()
+                .parse(s)
+                .into_result()
+                .map_err(|e| eyre!("Failed to parse sample {}: {e:?}", i + 1))
+        })
+        .collect::<Result<_>>()?;
+
+    let program = program
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(|l| {
+            Instruction::parser()
+                .parse(l)
+                .into_result()
+                .map_err(|e| eyre!("Failed to parse instruction `{l}`: {e:?}"))
+        })
+        .collect::<Result<_>>()?;
+
+    Ok(Manual { samples, program })
+}
+
+/// Works out which operation each opcode refers to, by narrowing each opcode down to the
+/// operations consistent with every sample, then repeatedly pinning down opcodes with only one
+/// candidate left.
+fn deduce_opcodes(samples: &[Sample]) -> Result<[Op; 16]> {
+    let mut candidates = [u16::MAX; 16];
+    let mut last_sample = [None; 16];
+
+    for (i, sample) in samples.iter().enumerate() {
+        let opcode = sample.instruction.opcode;
+        let Some(candidate) = candidates.get_mut(opcode) else {
+            return Err(eyre!("sample {}: invalid opcode {opcode}", i + 1));
+        };
+
+        *candidate &= sample.matching_ops();
+        last_sample[opcode] = Some(i);
+        if *candidate == 0 {
+            return Err(eyre!(
+                "sample {} leaves opcode {opcode} with no possible operation: {sample:?}",
+                i + 1
+            ));
+        }
+    }
+
+    let mut mapping = [None; 16];
+    while let Some(opcode) =
+        (0..16).find(|&o| mapping[o].is_none() && candidates[o].count_ones() == 1)
+    {
+        let op_idx = candidates[opcode].trailing_zeros() as usize;
+        mapping[opcode] = Some(Op::ALL[op_idx]);
+        for (other, candidate) in candidates.iter_mut().enumerate() {
+            if other != opcode {
+                *candidate &= !(1 << op_idx);
+            }
+        }
+    }
+
+    // Prefer to report an opcode we have samples for, as that's more likely to be the culprit.
+    let unresolved = (0..16)
+        .filter(|&o| mapping[o].is_none())
+        .min_by_key(|&o| last_sample[o].is_none());
+    if let Some(opcode) = unresolved {
+        let names: Vec<_> = (0..16)
+            .filter(|&i| candidates[opcode] & (1 << i) != 0)
+            .map(|i| Op::ALL[i].name())
+            .collect();
+
+        return Err(match last_sample[opcode] {
+            None => eyre!("no samples for opcode {opcode}"),
+            Some(_) if names.is_empty() => {
+                eyre!("opcode {opcode} has no operation left after eliminating the others")
+            }
+            Some(i) => eyre!(
+                "opcode {opcode} is ambiguous between {} as of sample {}: {:?}",
+                names.join(", "),
+                i + 1,
+                samples[i]
+            ),
+        });
+    }
+
+    Ok(mapping.map(Option::unwrap))
+}
+
+fn part1(manual: &Manual) -> usize {
+    manual
+        .samples
+        .iter()
+        .filter(|s| s.matching_ops().count_ones() >= 3)
+        .count()
+}
+
+fn part2(manual: &Manual) -> Result<usize> {
+    let mapping = deduce_opcodes(&manual.samples)?;
+    let mut regs = [0; 4];
+
+    for (i, instr) in manual.program.iter().enumerate() {
+        let op = *mapping
+            .get(instr.opcode)
+            .ok_or_else(|| eyre!("instruction {}: invalid opcode {}", i + 1, instr.opcode))?;
+        op.execute(instr.a, instr.b, instr.c, &mut regs)
+            .ok_or_else(|| eyre!("instruction {}: invalid register", i + 1))?;
+    }
+
+    Ok(regs[0])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_lib::Example;
+
+    #[test]
+    fn part1_test() {
+        let data = aoc_lib::input(DAY.day)
+            .example(Example::Part1, 1)
+            .open()
+            .unwrap();
+
+        let parsed = parse(&data).unwrap();
+        let expected = 1;
+        let actual = part1(&parsed);
+
+        assert_eq!(expected, actual);
+
+        let matching: Vec<_> = Op::ALL
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| parsed.samples[0].matching_ops() & (1 << i) != 0)
+            .map(|(_, op)| *op)
+            .collect();
+        assert_eq!(matching, [Op::Addi, Op::Mulr, Op::Seti]);
+    }
+
+    #[test]
+    fn contradiction_test() {
+        let input = "\
+Before: [3, 2, 1, 1]
+9 2 1 2
+After:  [3, 2, 2, 1]
+
+Before: [0, 0, 0, 0]
+9 0 0 0
+After:  [5, 5, 5, 5]
+";
+        let parsed = parse(input).unwrap();
+        let err = deduce_opcodes(&parsed.samples).unwrap_err().to_string();
+
+        assert!(err.contains("sample 2"), "{err}");
+    }
+
+    #[test]
+    fn ambiguity_test() {
+        let data = aoc_lib::input(DAY.day)
+            .example(Example::Part1, 1)
+            .open()
+            .unwrap();
+
+        let parsed = parse(&data).unwrap();
+        let err = deduce_opcodes(&parsed.samples).unwrap_err().to_string();
+
+        assert!(err.contains("sample 1"), "{err}");
+    }
+}
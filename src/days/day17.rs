@@ -0,0 +1,297 @@
+use std::ops::RangeInclusive;
+
+use aoc_lib::{Bench, BenchResult, Day, NoError, ParseResult, UserError};
+use chumsky::{primitive::just, text::digits, Parser as _};
+use color_eyre::{eyre::eyre, Report, Result};
+
+use crate::Parser;
+
+pub const DAY: Day = Day {
+    day: 17,
+    name: "Reservoir Research",
+    part_1: run_part1,
+    part_2: Some(run_part2),
+    other: &[("Parse", run_parse)],
+};
+
+fn run_part1(input: &str, b: Bench) -> BenchResult {
+    let data = parse(input).map_err(UserError)?;
+    b.bench(|| Ok::<_, NoError>(part1(&data)))
+}
+
+fn run_part2(input: &str, b: Bench) -> BenchResult {
+    let data = parse(input).map_err(UserError)?;
+    b.bench(|| Ok::<_, NoError>(part2(&data)))
+}
+
+fn run_parse(input: &str, b: Bench) -> BenchResult {
+    b.bench(|| {
+        let data = parse(input).map_err(UserError)?;
+        Ok::<_, Report>(ParseResult(data))
+    })
+}
+
+const SPRING_X: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Axis {
+    X,
+    Y,
+}
+
+#[derive(Debug, Clone)]
+struct Vein {
+    x: RangeInclusive<usize>,
+    y: RangeInclusive<usize>,
+}
+
+impl Vein {
+    fn parser<'a>() -> impl Parser<'a, (Axis, usize, Axis, usize, usize)> {
+        let number = digits(10).to_slice().from_str::<usize>().unwrapped();
+        let axis = just('x').to(Axis::X).or(just('y').to(Axis::Y));
+
+        axis.then_ignore(just('='))
+            .then(number)
+            .then_ignore(just(", "))
+            .then(axis)
+            .then_ignore(just('='))
+            .then(number)
+            .then_ignore(just(".."))
+            .then(number)
+            .map(|((((fixed_axis, fixed), range_axis), start), end)| {
+                (fixed_axis, fixed, range_axis, start, end)
+            })
+    }
+
+    fn parse(line: &str) -> Result<Vein> {
+        let (fixed_axis, fixed, range_axis, start, end) = Vein::parser()
+            .parse(line)
+            .into_result()
+            .map_err(|e| eyre!("Failed to parse: {e:?}"))?;
+
+        if start > end {
+            return Err(eyre!("backwards range in `{line}`"));
+        }
+
+        match (fixed_axis, range_axis) {
+            (Axis::X, Axis::Y) => Ok(Vein {
+                x: fixed..=fixed,
+                y: start..=end,
+            }),
+            (Axis::Y, Axis::X) => Ok(Vein {
+                x: start..=end,
+                y: fixed..=fixed,
+            }),
+            _ => Err(eyre!("vein uses the same axis twice: `{line}`")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tile {
+    Sand,
+    Clay,
+    Flowing,
+    Still,
+}
+
+impl Tile {
+    fn supports_water(self) -> bool {
+        matches!(self, Tile::Clay | Tile::Still)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Scan {
+    tiles: Vec<Tile>,
+    width: usize,
+    height: usize,
+    /// Real x coordinate of the first column.
+    min_x: usize,
+    /// The grid starts at the spring, but only tiles from here down are counted.
+    min_y: usize,
+}
+
+impl Scan {
+    fn idx(&self, x: usize, y: usize) -> usize {
+        y * self.width + x
+    }
+
+    fn get(&self, x: usize, y: usize) -> Tile {
+        self.tiles[self.idx(x, y)]
+    }
+
+    fn set(&mut self, x: usize, y: usize, tile: Tile) {
+        let idx = self.idx(x, y);
+        self.tiles[idx] = tile;
+    }
+
+    /// If the flowing water at `(x, y)` is part of a row held in by clay on both sides, returns
+    /// the columns spanned by that row.
+    fn enclosed_row(&self, x: usize, y: usize) -> Option<RangeInclusive<usize>> {
+        let is_held =
+            |cx: usize| self.get(cx, y) == Tile::Flowing && self.get(cx, y + 1).supports_water();
+
+        let mut left = x;
+        while is_held(left) {
+            left = left.checked_sub(1)?;
+        }
+        let mut right = x;
+        while is_held(right) {
+            right += 1;
+            if right == self.width {
+                return None;
+            }
+        }
+
+        (self.get(left, y) == Tile::Clay && self.get(right, y) == Tile::Clay)
+            .then_some(left + 1..=right - 1)
+    }
+
+    /// Lets water run from the spring until nothing changes.
+    ///
+    /// This uses a worklist of flowing tiles rather than recursing, as the falls in real inputs
+    /// can be deep enough to overflow the stack.
+    fn flow(&mut self) {
+        let spring_x = SPRING_X - self.min_x;
+        self.set(spring_x, 0, Tile::Flowing);
+        let mut stack = vec![(spring_x, 0)];
+
+        while let Some((x, y)) = stack.pop() {
+            // Tiles can settle after being queued.
+            if self.get(x, y) != Tile::Flowing || y + 1 == self.height {
+                continue;
+            }
+
+            match self.get(x, y + 1) {
+                Tile::Sand => {
+                    self.set(x, y + 1, Tile::Flowing);
+                    stack.push((x, y + 1));
+                }
+                Tile::Flowing => {}
+                Tile::Clay | Tile::Still => {
+                    for nx in [x.wrapping_sub(1), x + 1] {
+                        if nx < self.width && self.get(nx, y) == Tile::Sand {
+                            self.set(nx, y, Tile::Flowing);
+                            stack.push((nx, y));
+                        }
+                    }
+
+                    if let Some(row) = self.enclosed_row(x, y) {
+                        for cx in row {
+                            self.set(cx, y, Tile::Still);
+                            // Anything falling into this row now has something to spread out on.
+                            if y > 0 && self.get(cx, y - 1) == Tile::Flowing {
+                                stack.push((cx, y - 1));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn count(&self, pred: impl Fn(Tile) -> bool) -> usize {
+        self.tiles[self.min_y * self.width..]
+            .iter()
+            .filter(|&&t| pred(t))
+            .count()
+    }
+}
+
+fn parse(input: &str) -> Result<Scan> {
+    let veins: Vec<_> = input
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(Vein::parse)
+        .collect::<Result<_>>()?;
+
+    let min_y = veins.iter().map(|v| *v.y.start()).min();
+    let max_y = veins.iter().map(|v| *v.y.end()).max();
+    let (Some(min_y), Some(max_y)) = (min_y, max_y) else {
+        return Err(eyre!("no clay in scan"));
+    };
+
+    // Leave a spare column either side for water to fall down past the outermost clay.
+    let min_x = veins
+        .iter()
+        .map(|v| *v.x.start())
+        .chain([SPRING_X])
+        .min()
+        .unwrap()
+        .saturating_sub(1);
+    let max_x = veins
+        .iter()
+        .map(|v| *v.x.end())
+        .chain([SPRING_X])
+        .max()
+        .unwrap()
+        + 1;
+
+    let width = max_x - min_x + 1;
+    let height = max_y + 1;
+    let mut scan = Scan {
+        tiles: vec![Tile::Sand; width * height],
+        width,
+        height,
+        min_x,
+        min_y,
+    };
+
+    for vein in &veins {
+        for y in vein.y.clone() {
+            for x in vein.x.clone() {
+                scan.set(x - min_x, y, Tile::Clay);
+            }
+        }
+    }
+
+    Ok(scan)
+}
+
+fn part1(scan: &Scan) -> usize {
+    let mut scan = scan.clone();
+    scan.flow();
+    scan.count(|t| matches!(t, Tile::Flowing | Tile::Still))
+}
+
+fn part2(scan: &Scan) -> usize {
+    let mut scan = scan.clone();
+    scan.flow();
+    scan.count(|t| t == Tile::Still)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_lib::Example;
+
+    #[test]
+    fn part1_test() {
+        let data = aoc_lib::input(DAY.day)
+            .example(Example::Part1, 1)
+            .open()
+            .unwrap();
+
+        let parsed = parse(&data).unwrap();
+        let expected = 57;
+        let actual = part1(&parsed);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn part2_test() {
+        let data = aoc_lib::input(DAY.day)
+            .example(Example::Part1, 1)
+            .open()
+            .unwrap();
+
+        let parsed = parse(&data).unwrap();
+        let expected = 29;
+        let actual = part2(&parsed);
+
+        assert_eq!(expected, actual);
+    }
+}
@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+
+use aoc_lib::{Bench, BenchResult, Day, NoError, ParseResult, UserError};
+use color_eyre::{eyre::eyre, Report, Result};
+
+pub const DAY: Day = Day {
+    day: 18,
+    name: "Settlers of The North Pole",
+    part_1: run_part1,
+    part_2: Some(run_part2),
+    other: &[("Parse", run_parse)],
+};
+
+fn run_part1(input: &str, b: Bench) -> BenchResult {
+    let data = parse(input).map_err(UserError)?;
+    b.bench(|| Ok::<_, NoError>(part1(&data)))
+}
+
+fn run_part2(input: &str, b: Bench) -> BenchResult {
+    let data = parse(input).map_err(UserError)?;
+    b.bench(|| Ok::<_, NoError>(part2(&data)))
+}
+
+fn run_parse(input: &str, b: Bench) -> BenchResult {
+    b.bench(|| {
+        let data = parse(input).map_err(UserError)?;
+        Ok::<_, Report>(ParseResult(data))
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Acre {
+    Open,
+    Trees,
+    Lumberyard,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Area {
+    acres: Vec<Acre>,
+    width: usize,
+    height: usize,
+}
+
+impl Area {
+    fn step(&self) -> Area {
+        let mut acres = Vec::with_capacity(self.acres.len());
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let (mut trees, mut lumberyards) = (0, 0);
+                for ny in y.saturating_sub(1)..=(y + 1).min(self.height - 1) {
+                    for nx in x.saturating_sub(1)..=(x + 1).min(self.width - 1) {
+                        if (nx, ny) == (x, y) {
+                            continue;
+                        }
+                        match self.acres[ny * self.width + nx] {
+                            Acre::Trees => trees += 1,
+                            Acre::Lumberyard => lumberyards += 1,
+                            Acre::Open => {}
+                        }
+                    }
+                }
+
+                let next = match self.acres[y * self.width + x] {
+                    Acre::Open if trees >= 3 => Acre::Trees,
+                    Acre::Trees if lumberyards >= 3 => Acre::Lumberyard,
+                    Acre::Lumberyard if lumberyards == 0 || trees == 0 => Acre::Open,
+                    acre => acre,
+                };
+                acres.push(next);
+            }
+        }
+
+        Area {
+            acres,
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    fn resource_value(&self) -> usize {
+        let count = |kind| self.acres.iter().filter(|&&a| a == kind).count();
+        count(Acre::Trees) * count(Acre::Lumberyard)
+    }
+
+    /// Runs the area forward, skipping ahead once it falls into a repeating cycle of states.
+    fn after(&self, minutes: usize) -> Area {
+        let mut seen = HashMap::new();
+        let mut history = Vec::new();
+        let mut cur = self.clone();
+
+        for minute in 0..minutes {
+            if let Some(&start) = seen.get(&cur.acres) {
+                let cycle_len = minute - start;
+                let offset = (minutes - start) % cycle_len;
+                return history.swap_remove(start + offset);
+            }
+
+            seen.insert(cur.acres.clone(), minute);
+            let next = cur.step();
+            history.push(cur);
+            cur = next;
+        }
+
+        cur
+    }
+}
+
+fn parse(input: &str) -> Result<Area> {
+    let mut acres = Vec::new();
+    let mut width = None;
+    let mut height = 0;
+
+    for line in input.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        if *width.get_or_insert(line.len()) != line.len() {
+            return Err(eyre!(
+                "line {} has a different width to the first",
+                height + 1
+            ));
+        }
+
+        for b in line.bytes() {
+            acres.push(match b {
+                b'.' => Acre::Open,
+                b'|' => Acre::Trees,
+                b'#' => Acre::Lumberyard,
+                _ => return Err(eyre!("invalid acre: `{}`", b as char)),
+            });
+        }
+        height += 1;
+    }
+
+    Ok(Area {
+        acres,
+        width: width.ok_or_else(|| eyre!("empty input"))?,
+        height,
+    })
+}
+
+fn part1(area: &Area) -> usize {
+    area.after(10).resource_value()
+}
+
+fn part2(area: &Area) -> usize {
+    area.after(1_000_000_000).resource_value()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_lib::Example;
+
+    #[test]
+    fn part1_test() {
+        let data = aoc_lib::input(DAY.day)
+            .example(Example::Part1, 1)
+            .open()
+            .unwrap();
+
+        let parsed = parse(&data).unwrap();
+        let expected = 1147;
+        let actual = part1(&parsed);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn cycle_skip_test() {
+        let data = aoc_lib::input(DAY.day)
+            .example(Example::Part1, 1)
+            .open()
+            .unwrap();
+
+        let parsed = parse(&data).unwrap();
+        let mut expected = parsed.clone();
+        for _ in 0..1000 {
+            expected = expected.step();
+        }
+        let actual = parsed.after(1000);
+
+        assert_eq!(expected, actual);
+    }
+}
@@ -0,0 +1,353 @@
+use aoc_lib::{Bench, BenchResult, Day, ParseResult, UserError};
+use color_eyre::{eyre::eyre, Report, Result};
+
+use super::day16::Op;
+
+pub const DAY: Day = Day {
+    day: 19,
+    name: "Go With The Flow",
+    part_1: run_part1,
+    part_2: Some(run_part2),
+    other: &[("Parse", run_parse)],
+};
+
+fn run_part1(input: &str, b: Bench) -> BenchResult {
+    let data = Program::parse(input).map_err(UserError)?;
+    b.bench(|| part1(&data))
+}
+
+fn run_part2(input: &str, b: Bench) -> BenchResult {
+    let data = Program::parse(input).map_err(UserError)?;
+    b.bench(|| part2(&data))
+}
+
+fn run_parse(input: &str, b: Bench) -> BenchResult {
+    b.bench(|| {
+        let data = Program::parse(input).map_err(UserError)?;
+        Ok::<_, Report>(ParseResult(data))
+    })
+}
+
+pub(super) const NUM_REGISTERS: usize = 6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct Instruction {
+    pub(super) op: Op,
+    pub(super) a: usize,
+    pub(super) b: usize,
+    pub(super) c: usize,
+}
+
+impl Instruction {
+    fn parse(line: &str) -> Result<Instruction> {
+        let mut parts = line.split_whitespace();
+        let op = parts
+            .next()
+            .and_then(Op::from_name)
+            .ok_or_else(|| eyre!("invalid operation in `{line}`"))?;
+
+        let mut operand = || -> Result<usize> {
+            let part = parts
+                .next()
+                .ok_or_else(|| eyre!("missing operand in `{line}`"))?;
+            Ok(part.parse()?)
+        };
+        let (a, b, c) = (operand()?, operand()?, operand()?);
+
+        if parts.next().is_some() {
+            return Err(eyre!("too many operands in `{line}`"));
+        }
+
+        Ok(Instruction { op, a, b, c })
+    }
+
+    /// Whether this is `op` reading registers `x` and `y` in either order, and writing to `c`.
+    fn is_either_way(&self, op: Op, x: usize, y: usize, c: usize) -> bool {
+        self.op == op && self.c == c && ((self.a, self.b) == (x, y) || (self.a, self.b) == (y, x))
+    }
+
+    /// Whether this is `op` with the given operands, where `None` matches anything.
+    fn is(&self, op: Op, a: Option<usize>, b: Option<usize>, c: Option<usize>) -> bool {
+        self.op == op
+            && a.unwrap_or(self.a) == self.a
+            && b.unwrap_or(self.b) == self.b
+            && c.unwrap_or(self.c) == self.c
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct Program {
+    pub(super) ip_reg: usize,
+    pub(super) instructions: Vec<Instruction>,
+}
+
+impl Program {
+    pub(super) fn parse(input: &str) -> Result<Program> {
+        let mut lines = input.lines().map(str::trim).filter(|l| !l.is_empty());
+
+        let ip_reg: usize = lines
+            .next()
+            .and_then(|l| l.strip_prefix("#ip "))
+            .ok_or_else(|| eyre!("missing `#ip` directive"))?
+            .parse()?;
+        if ip_reg >= NUM_REGISTERS {
+            return Err(eyre!(
+                "instruction pointer bound to invalid register {ip_reg}"
+            ));
+        }
+
+        let instructions = lines.map(Instruction::parse).collect::<Result<_>>()?;
+
+        Ok(Program {
+            ip_reg,
+            instructions,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub(super) struct Machine {
+    pub(super) regs: [usize; NUM_REGISTERS],
+    pub(super) ip: usize,
+}
+
+impl Machine {
+    /// Executes the instruction at the instruction pointer.
+    ///
+    /// Returns `false` if the instruction pointer is outside the program, meaning it has halted.
+    pub(super) fn step(&mut self, program: &Program) -> Result<bool> {
+        let Some(instr) = program.instructions.get(self.ip) else {
+            return Ok(false);
+        };
+
+        self.regs[program.ip_reg] = self.ip;
+        instr
+            .op
+            .execute(instr.a, instr.b, instr.c, &mut self.regs)
+            .ok_or_else(|| eyre!("invalid register in instruction {}", self.ip))?;
+        self.ip = self.regs[program.ip_reg] + 1;
+
+        Ok(true)
+    }
+}
+
+/// The nested loops of the divisor sum, as found in the puzzle inputs.
+///
+/// ```text
+/// start:  seti 1 _ outer
+/// inner:  seti 1 _ inner
+/// body:   mulr outer inner tmp
+///         eqrr tmp target tmp
+///         addr tmp ip ip
+///         addi ip 1 ip
+///         addr outer 0 0
+///         addi inner 1 inner
+///         gtrr inner target tmp
+///         addr ip tmp ip
+///         seti body-1 _ ip
+///         addi outer 1 outer
+///         gtrr outer target tmp
+///         addr tmp ip ip
+///         seti inner-1 _ ip
+/// ```
+///
+/// Operands of `mulr`, `eqrr` and `addr` can be either way round.
+#[derive(Debug, Clone, Copy)]
+struct DivisorLoop {
+    start: usize,
+    outer: usize,
+    inner: usize,
+    tmp: usize,
+    target: usize,
+}
+
+impl DivisorLoop {
+    const LEN: usize = 15;
+
+    fn find(program: &Program) -> Option<DivisorLoop> {
+        let ip = program.ip_reg;
+
+        program
+            .instructions
+            .windows(Self::LEN)
+            .enumerate()
+            .find_map(|(start, w)| {
+                let [outer_init, inner_init, mul, eq, skip, jump, acc, rest @ ..] = w else {
+                    return None;
+                };
+                let [inner_inc, inner_cmp, inner_skip, inner_jump, rest @ ..] = rest else {
+                    return None;
+                };
+                let [outer_inc, outer_cmp, outer_skip, outer_jump] = rest else {
+                    return None;
+                };
+
+                let (outer, inner, tmp) = (outer_init.c, inner_init.c, mul.c);
+                let target = if eq.a == tmp { eq.b } else { eq.a };
+
+                let registers = [0, ip, outer, inner, tmp, target];
+                let distinct = registers
+                    .iter()
+                    .enumerate()
+                    .all(|(i, r)| *r < NUM_REGISTERS && !registers[..i].contains(r));
+
+                let matches = distinct
+                    && outer_init.is(Op::Seti, Some(1), None, Some(outer))
+                    && inner_init.is(Op::Seti, Some(1), None, Some(inner))
+                    && mul.is_either_way(Op::Mulr, outer, inner, tmp)
+                    && eq.is_either_way(Op::Eqrr, tmp, target, tmp)
+                    && skip.is_either_way(Op::Addr, tmp, ip, ip)
+                    && jump.is(Op::Addi, Some(ip), Some(1), Some(ip))
+                    && acc.is_either_way(Op::Addr, outer, 0, 0)
+                    && inner_inc.is(Op::Addi, Some(inner), Some(1), Some(inner))
+                    && inner_cmp.is(Op::Gtrr, Some(inner), Some(target), Some(tmp))
+                    && inner_skip.is_either_way(Op::Addr, tmp, ip, ip)
+                    && inner_jump.is(Op::Seti, Some(start + 1), None, Some(ip))
+                    && outer_inc.is(Op::Addi, Some(outer), Some(1), Some(outer))
+                    && outer_cmp.is(Op::Gtrr, Some(outer), Some(target), Some(tmp))
+                    && outer_skip.is_either_way(Op::Addr, tmp, ip, ip)
+                    && outer_jump.is(Op::Seti, Some(start), None, Some(ip));
+
+                matches.then_some(DivisorLoop {
+                    start,
+                    outer,
+                    inner,
+                    tmp,
+                    target,
+                })
+            })
+    }
+
+    /// Does what the loops would, in one go, leaving the machine just past them.
+    fn apply(&self, machine: &mut Machine) {
+        let target = machine.regs[self.target];
+        machine.regs[0] += divisor_sum(target);
+        machine.regs[self.outer] = target.max(1) + 1;
+        machine.regs[self.inner] = target.max(1) + 1;
+        machine.regs[self.tmp] = 1;
+        machine.ip = self.start + Self::LEN;
+    }
+}
+
+fn divisor_sum(n: usize) -> usize {
+    let mut sum = 0;
+    let mut i = 1;
+
+    while i * i <= n {
+        if n.is_multiple_of(i) {
+            sum += i;
+            if i * i != n {
+                sum += n / i;
+            }
+        }
+        i += 1;
+    }
+
+    sum
+}
+
+/// Runs the program literally until it halts, returning register 0.
+fn execute(program: &Program, r0: usize) -> Result<usize> {
+    let mut machine = Machine::default();
+    machine.regs[0] = r0;
+
+    while machine.step(program)? {}
+
+    Ok(machine.regs[0])
+}
+
+/// Runs the program, but if it contains the usual divisor sum then computes the sum directly
+/// whenever the program reaches it.
+fn run(program: &Program, r0: usize) -> Result<usize> {
+    let Some(divisor_loop) = DivisorLoop::find(program) else {
+        return execute(program, r0);
+    };
+
+    let mut machine = Machine::default();
+    machine.regs[0] = r0;
+
+    loop {
+        if machine.ip == divisor_loop.start {
+            divisor_loop.apply(&mut machine);
+        } else if !machine.step(program)? {
+            return Ok(machine.regs[0]);
+        }
+    }
+}
+
+fn part1(program: &Program) -> Result<usize> {
+    run(program, 0)
+}
+
+fn part2(program: &Program) -> Result<usize> {
+    run(program, 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_lib::Example;
+
+    #[test]
+    fn part1_test() {
+        let data = aoc_lib::input(DAY.day)
+            .example(Example::Part1, 1)
+            .open()
+            .unwrap();
+
+        let parsed = Program::parse(&data).unwrap();
+        let expected = 6;
+        let actual = part1(&parsed).unwrap();
+
+        assert_eq!(expected, actual);
+    }
+
+    const DIVISOR_PROGRAM: &str = "\
+#ip 3
+addi 3 16 3
+seti 1 2 5
+seti 1 0 4
+mulr 5 4 2
+eqrr 2 1 2
+addr 2 3 3
+addi 3 1 3
+addr 5 0 0
+addi 4 1 4
+gtrr 4 1 2
+addr 3 2 3
+seti 2 3 3
+addi 5 1 5
+gtrr 5 1 2
+addr 2 3 3
+seti 1 6 3
+mulr 3 3 3
+seti 36 0 1
+seti 0 0 3
+";
+
+    #[test]
+    fn divisor_loop_test() {
+        let parsed = Program::parse(DIVISOR_PROGRAM).unwrap();
+        assert!(DivisorLoop::find(&parsed).is_some());
+
+        let expected = execute(&parsed, 0).unwrap();
+        let actual = run(&parsed, 0).unwrap();
+
+        assert_eq!(expected, 91);
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn near_miss_test() {
+        // Stepping the outer counter by 2 only sums the odd divisors.
+        let input = DIVISOR_PROGRAM.replace("addi 5 1 5", "addi 5 2 5");
+        let parsed = Program::parse(&input).unwrap();
+        assert!(DivisorLoop::find(&parsed).is_none());
+
+        let expected = execute(&parsed, 0).unwrap();
+        let actual = run(&parsed, 0).unwrap();
+
+        assert_eq!(expected, 13);
+        assert_eq!(expected, actual);
+    }
+}
@@ -0,0 +1,223 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use aoc_lib::{Bench, BenchResult, Day, NoError, ParseResult, UserError};
+use chumsky::{
+    primitive::{choice, just},
+    recursive::recursive,
+    IterParser, Parser as _,
+};
+use color_eyre::{eyre::eyre, Report, Result};
+
+use crate::Parser;
+
+pub const DAY: Day = Day {
+    day: 20,
+    name: "A Regular Map",
+    part_1: run_part1,
+    part_2: Some(run_part2),
+    other: &[("Parse", run_parse)],
+};
+
+fn run_part1(input: &str, b: Bench) -> BenchResult {
+    let data = parse(input).map_err(UserError)?;
+    b.bench(|| Ok::<_, NoError>(part1(&data)))
+}
+
+fn run_part2(input: &str, b: Bench) -> BenchResult {
+    let data = parse(input).map_err(UserError)?;
+    b.bench(|| Ok::<_, NoError>(part2::<1000>(&data)))
+}
+
+fn run_parse(input: &str, b: Bench) -> BenchResult {
+    b.bench(|| {
+        let data = parse(input).map_err(UserError)?;
+        Ok::<_, Report>(ParseResult(data))
+    })
+}
+
+type Pos = (i32, i32);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    North,
+    South,
+    East,
+    West,
+}
+
+impl Direction {
+    fn step(self, (x, y): Pos) -> Pos {
+        match self {
+            Direction::North => (x, y - 1),
+            Direction::South => (x, y + 1),
+            Direction::East => (x + 1, y),
+            Direction::West => (x - 1, y),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Node {
+    Move(Direction),
+    Branch(Vec<Vec<Node>>),
+}
+
+impl Node {
+    fn parser<'a>() -> impl Parser<'a, Vec<Node>> {
+        let direction = choice((
+            just('N').to(Direction::North),
+            just('S').to(Direction::South),
+            just('E').to(Direction::East),
+            just('W').to(Direction::West),
+        ))
+        .map(Node::Move);
+
+        let sequence = recursive(|sequence| {
+            let branch = sequence
+                .separated_by(just('|'))
+                .at_least(1)
+                .collect::<Vec<_>>()
+                .delimited_by(just('('), just(')'))
+                .map(Node::Branch);
+
+            direction.or(branch).repeated().collect::<Vec<_>>()
+        });
+
+        sequence.delimited_by(just('^'), just('$'))
+    }
+}
+
+/// Follows every route through `nodes` from each of `starts`, recording the doors passed through,
+/// and returns every room the routes could end up in.
+fn walk(nodes: &[Node], starts: HashSet<Pos>, doors: &mut HashMap<Pos, Vec<Pos>>) -> HashSet<Pos> {
+    let mut current = starts;
+
+    for node in nodes {
+        current = match node {
+            Node::Move(dir) => current
+                .into_iter()
+                .map(|from| {
+                    let to = dir.step(from);
+                    for (a, b) in [(from, to), (to, from)] {
+                        let neighbours = doors.entry(a).or_default();
+                        if !neighbours.contains(&b) {
+                            neighbours.push(b);
+                        }
+                    }
+                    to
+                })
+                .collect(),
+            Node::Branch(options) => options
+                .iter()
+                .flat_map(|option| walk(option, current.clone(), doors))
+                .collect(),
+        };
+    }
+
+    current
+}
+
+#[derive(Debug, Clone)]
+struct Facility {
+    /// Doors leading out of each room.
+    doors: HashMap<Pos, Vec<Pos>>,
+}
+
+impl Facility {
+    /// Fewest doors needed to reach each room from the start.
+    fn distances(&self) -> HashMap<Pos, u32> {
+        let mut dists = HashMap::from([((0, 0), 0)]);
+        let mut queue = VecDeque::from([(0, 0)]);
+
+        while let Some(room) = queue.pop_front() {
+            let dist = dists[&room];
+            for &next in self.doors.get(&room).into_iter().flatten() {
+                dists.entry(next).or_insert_with(|| {
+                    queue.push_back(next);
+                    dist + 1
+                });
+            }
+        }
+
+        dists
+    }
+}
+
+fn parse(input: &str) -> Result<Facility> {
+    let route = Node::parser()
+        .parse(input.trim())
+        .into_result()
+        .map_err(|e| eyre!("Failed to parse: {e:?}"))?;
+
+    let mut doors = HashMap::new();
+    walk(&route, HashSet::from([(0, 0)]), &mut doors);
+
+    Ok(Facility { doors })
+}
+
+fn part1(facility: &Facility) -> u32 {
+    facility.distances().into_values().max().unwrap_or(0)
+}
+
+fn part2<const MIN_DOORS: u32>(facility: &Facility) -> usize {
+    facility
+        .distances()
+        .into_values()
+        .filter(|&d| d >= MIN_DOORS)
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part1_test() {
+        let tests = [
+            ("^WNE$", 3),
+            ("^ENWWW(NEEE|SSE(EE|N))$", 10),
+            ("^ENNWSWW(NEWS|)SSSEEN(WNSE|)EE(SWEN|)NNN$", 18),
+            ("^ESSWWN(E|NNENN(EESS(WNSE|)SSS|WWWSSSSE(SW|NNNE)))$", 23),
+            (
+                "^WSSEESWWWNW(S|NENNEEEENN(ESSSSW(NWSW|SSEN)|WSWWN(E|WWS(E|SS))))$",
+                31,
+            ),
+        ];
+
+        for (input, expected) in tests {
+            let parsed = parse(input).unwrap();
+            let actual = part1(&parsed);
+
+            assert_eq!(expected, actual, "{input}");
+        }
+    }
+
+    #[test]
+    fn part2_test() {
+        let parsed = parse("^ENWWW(NEEE|SSE(EE|N))$").unwrap();
+        let expected = 10;
+        let actual = part2::<6>(&parsed);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn malformed_test() {
+        let tests = [
+            ("^WN(E|S$", 7),
+            ("^WNE)$", 4),
+            ("^WNX$", 3),
+            ("WNE$", 0),
+            ("^WNE", 4),
+        ];
+
+        for (input, at) in tests {
+            let err = parse(input).unwrap_err().to_string();
+
+            // Only the rich error type keeps track of spans.
+            if cfg!(not(feature = "testing")) {
+                assert!(err.contains(&format!("at {at}..")), "{input}: {err}");
+            }
+        }
+    }
+}
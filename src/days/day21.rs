@@ -0,0 +1,286 @@
+use std::collections::HashSet;
+
+use aoc_lib::{Bench, BenchResult, Day, ParseResult, UserError};
+use color_eyre::{eyre::eyre, Report, Result};
+
+use super::{
+    day16::Op,
+    day19::{Machine, Program},
+};
+
+pub const DAY: Day = Day {
+    day: 21,
+    name: "Chronal Conversion",
+    part_1: run_part1,
+    part_2: Some(run_part2),
+    other: &[("Parse", run_parse)],
+};
+
+fn run_part1(input: &str, b: Bench) -> BenchResult {
+    let data = Program::parse(input).map_err(UserError)?;
+    b.bench(|| part1(&data))
+}
+
+fn run_part2(input: &str, b: Bench) -> BenchResult {
+    let data = Program::parse(input).map_err(UserError)?;
+    b.bench(|| part2(&data))
+}
+
+fn run_parse(input: &str, b: Bench) -> BenchResult {
+    b.bench(|| {
+        let data = Program::parse(input).map_err(UserError)?;
+        Ok::<_, Report>(ParseResult(data))
+    })
+}
+
+/// The loop the elf program uses to divide by shifting, as found in the puzzle inputs.
+///
+/// ```text
+/// start:   seti 0 _ quotient
+/// loop:    addi quotient 1 tmp
+///          muli tmp divisor tmp
+///          gtrr tmp dividend tmp
+///          addr tmp ip ip
+///          addi ip 1 ip
+///          seti done _ ip
+///          addi quotient 1 quotient
+///          seti loop _ ip
+/// ```
+#[derive(Debug, Clone, Copy)]
+struct DivideLoop {
+    start: usize,
+    quotient: usize,
+    tmp: usize,
+    divisor: usize,
+    dividend: usize,
+    exit: usize,
+}
+
+impl DivideLoop {
+    fn find(program: &Program) -> Option<DivideLoop> {
+        let ip = program.ip_reg;
+
+        program
+            .instructions
+            .windows(9)
+            .enumerate()
+            .find_map(|(start, w)| {
+                let [init, inc, mul, cmp, skip, jump, exit, next, repeat] = w else {
+                    return None;
+                };
+
+                let quotient = init.c;
+                let tmp = inc.c;
+                let matches = init.op == Op::Seti
+                    && init.a == 0
+                    && inc.op == Op::Addi
+                    && (inc.a, inc.b) == (quotient, 1)
+                    && mul.op == Op::Muli
+                    && (mul.a, mul.c) == (tmp, tmp)
+                    && cmp.op == Op::Gtrr
+                    && (cmp.a, cmp.c) == (tmp, tmp)
+                    && skip.op == Op::Addr
+                    && (skip.a, skip.b, skip.c) == (tmp, ip, ip)
+                    && jump.op == Op::Addi
+                    && (jump.a, jump.b, jump.c) == (ip, 1, ip)
+                    && exit.op == Op::Seti
+                    && exit.c == ip
+                    && next.op == Op::Addi
+                    && (next.a, next.b, next.c) == (quotient, 1, quotient)
+                    && repeat.op == Op::Seti
+                    && (repeat.a, repeat.c) == (start, ip)
+                    && ![quotient, tmp, cmp.b].contains(&ip)
+                    && mul.b > 0;
+
+                matches.then_some(DivideLoop {
+                    start,
+                    quotient,
+                    tmp,
+                    divisor: mul.b,
+                    dividend: cmp.b,
+                    exit: exit.a + 1,
+                })
+            })
+    }
+
+    /// Does what the loop would, in one go.
+    fn apply(&self, machine: &mut Machine) {
+        let quotient = machine.regs[self.dividend] / self.divisor;
+        machine.regs[self.quotient] = quotient;
+        machine.regs[self.tmp] = 1;
+        machine.ip = self.exit;
+    }
+}
+
+/// The values register 0 is compared against at the halting check, in the order the program
+/// checks them.
+struct HaltingValues<'a> {
+    program: &'a Program,
+    machine: Machine,
+    check_ip: usize,
+    compared_reg: usize,
+    divide_loop: Option<DivideLoop>,
+}
+
+impl<'a> HaltingValues<'a> {
+    fn new(program: &'a Program, recognise_loops: bool) -> Result<Self> {
+        let (check_ip, compared_reg) = program
+            .instructions
+            .iter()
+            .enumerate()
+            .find_map(|(i, instr)| match (instr.op, instr.a, instr.b) {
+                (Op::Eqrr, 0, 0) => None,
+                (Op::Eqrr, 0, r) | (Op::Eqrr, r, 0) => Some((i, r)),
+                _ => None,
+            })
+            .ok_or_else(|| eyre!("no comparison against register 0 found"))?;
+
+        Ok(Self {
+            program,
+            machine: Machine::default(),
+            check_ip,
+            compared_reg,
+            divide_loop: recognise_loops.then(|| DivideLoop::find(program)).flatten(),
+        })
+    }
+}
+
+impl Iterator for HaltingValues<'_> {
+    type Item = Result<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(divide_loop) = self.divide_loop {
+                if self.machine.ip == divide_loop.start {
+                    divide_loop.apply(&mut self.machine);
+                    continue;
+                }
+            }
+
+            // Read the value before the check runs, in case the check overwrites its own operand.
+            let compared =
+                (self.machine.ip == self.check_ip).then(|| self.machine.regs[self.compared_reg]);
+            match self.machine.step(self.program) {
+                Ok(true) => {}
+                Ok(false) => return None,
+                Err(e) => return Some(Err(e)),
+            }
+
+            if let Some(value) = compared {
+                return Some(Ok(value));
+            }
+        }
+    }
+}
+
+fn part1(program: &Program) -> Result<usize> {
+    HaltingValues::new(program, true)?
+        .next()
+        .ok_or_else(|| eyre!("program halted before the check"))?
+}
+
+/// The last value before the sequence starts repeating.
+fn last_new_value(values: HaltingValues) -> Result<usize> {
+    let mut seen = HashSet::new();
+    let mut last = None;
+
+    // The values come from a generator with finite state, so they must eventually repeat.
+    for value in values {
+        let value = value?;
+        if !seen.insert(value) {
+            break;
+        }
+        last = Some(value);
+    }
+
+    last.ok_or_else(|| eyre!("program halted before the check"))
+}
+
+fn part2(program: &Program) -> Result<usize> {
+    last_new_value(HaltingValues::new(program, true)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The usual shape of the elf program, but with the hash cut down to 16 bits so the values
+    /// repeat quickly enough to check against literal execution.
+    const PROGRAM: &str = "\
+#ip 1
+seti 123 0 3
+bani 3 456 3
+eqri 3 72 3
+addr 3 1 1
+seti 0 0 1
+seti 0 6 3
+bori 3 65536 4
+seti 1234567 8 3
+bani 4 255 5
+addr 3 5 3
+bani 3 65535 3
+muli 3 65899 3
+bani 3 65535 3
+gtir 256 4 5
+addr 5 1 1
+addi 1 1 1
+seti 27 6 1
+seti 0 1 5
+addi 5 1 2
+muli 2 256 2
+gtrr 2 4 2
+addr 2 1 1
+addi 1 1 1
+seti 25 1 1
+addi 5 1 5
+seti 17 8 1
+setr 5 2 4
+seti 7 9 1
+eqrr 3 0 5
+addr 5 1 1
+seti 5 3 1
+";
+
+    #[test]
+    fn divide_loop_test() {
+        let parsed = Program::parse(PROGRAM).unwrap();
+        assert!(DivideLoop::find(&parsed).is_some());
+
+        let expected: Vec<_> = HaltingValues::new(&parsed, false)
+            .unwrap()
+            .take(50)
+            .collect::<Result<_>>()
+            .unwrap();
+        let actual: Vec<_> = HaltingValues::new(&parsed, true)
+            .unwrap()
+            .take(50)
+            .collect::<Result<_>>()
+            .unwrap();
+
+        assert_eq!(expected, actual);
+        assert_eq!(expected[0], part1(&parsed).unwrap());
+    }
+
+    #[test]
+    fn part2_test() {
+        let parsed = Program::parse(PROGRAM).unwrap();
+
+        let expected = last_new_value(HaltingValues::new(&parsed, false).unwrap()).unwrap();
+        let actual = last_new_value(HaltingValues::new(&parsed, true).unwrap()).unwrap();
+
+        assert_eq!(expected, actual);
+        assert_eq!(expected, part2(&parsed).unwrap());
+    }
+
+    #[test]
+    fn overwritten_operand_test() {
+        let parsed = Program::parse(PROGRAM).unwrap();
+        let expected = part1(&parsed).unwrap();
+
+        // The check stores its result over the value it compared, so it must be read beforehand.
+        let program = PROGRAM.replace("eqrr 3 0 5\naddr 5 1 1", "eqrr 3 0 3\naddr 3 1 1");
+        let parsed = Program::parse(&program).unwrap();
+
+        assert_eq!(expected, part1(&parsed).unwrap());
+    }
+}
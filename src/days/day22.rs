@@ -0,0 +1,232 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+};
+
+use aoc_lib::{Bench, BenchResult, Day, NoError, ParseResult, UserError};
+use color_eyre::{eyre::eyre, Report, Result};
+
+pub const DAY: Day = Day {
+    day: 22,
+    name: "Mode Maze",
+    part_1: run_part1,
+    part_2: Some(run_part2),
+    other: &[("Parse", run_parse)],
+};
+
+fn run_part1(input: &str, b: Bench) -> BenchResult {
+    let data = parse(input).map_err(UserError)?;
+    b.bench(|| Ok::<_, NoError>(part1(data)))
+}
+
+fn run_part2(input: &str, b: Bench) -> BenchResult {
+    let data = parse(input).map_err(UserError)?;
+    b.bench(|| Ok::<_, NoError>(part2(data)))
+}
+
+fn run_parse(input: &str, b: Bench) -> BenchResult {
+    b.bench(|| {
+        let data = parse(input).map_err(UserError)?;
+        Ok::<_, Report>(ParseResult(data))
+    })
+}
+
+const EROSION_MOD: usize = 20183;
+const SWITCH_TIME: u32 = 7;
+
+#[derive(Debug, Clone, Copy)]
+struct Scan {
+    depth: usize,
+    target: (usize, usize),
+}
+
+fn parse(input: &str) -> Result<Scan> {
+    let mut lines = input.lines().map(str::trim).filter(|l| !l.is_empty());
+
+    let depth = lines
+        .next()
+        .and_then(|l| l.strip_prefix("depth: "))
+        .ok_or_else(|| eyre!("missing depth"))?
+        .parse()?;
+
+    let target = lines
+        .next()
+        .and_then(|l| l.strip_prefix("target: "))
+        .ok_or_else(|| eyre!("missing target"))?;
+    let (x, y) = target
+        .split_once(',')
+        .ok_or_else(|| eyre!("invalid target: `{target}`"))?;
+
+    Ok(Scan {
+        depth,
+        target: (x.trim().parse()?, y.trim().parse()?),
+    })
+}
+
+/// Region types, numbered so that a region's type is also the one tool that can't be used there.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Region {
+    Rocky = 0,
+    Wet = 1,
+    Narrow = 2,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+enum Tool {
+    Neither = 0,
+    Torch = 1,
+    ClimbingGear = 2,
+}
+
+impl Tool {
+    fn usable_in(self, region: Region) -> bool {
+        self as u8 != region as u8
+    }
+}
+
+/// The cave's erosion levels, calculated as far out as we've needed so far.
+#[derive(Debug)]
+struct Cave {
+    scan: Scan,
+    erosion: Vec<Vec<usize>>,
+}
+
+impl Cave {
+    fn new(scan: Scan) -> Self {
+        Self {
+            scan,
+            erosion: Vec::new(),
+        }
+    }
+
+    /// Makes sure the erosion level at `(x, y)` is known, extending every row as needed. Each cell
+    /// only depends on the ones above and to the left, so filling in reading order is enough.
+    fn extend_to(&mut self, x: usize, y: usize) {
+        let width = self.erosion.first().map_or(0, Vec::len).max(x + 1);
+        let height = self.erosion.len().max(y + 1);
+        self.erosion.resize_with(height, Vec::new);
+
+        for cy in 0..height {
+            for cx in self.erosion[cy].len()..width {
+                let geologic_index = if (cx, cy) == (0, 0) || (cx, cy) == self.scan.target {
+                    0
+                } else if cy == 0 {
+                    cx * 16807
+                } else if cx == 0 {
+                    cy * 48271
+                } else {
+                    self.erosion[cy][cx - 1] * self.erosion[cy - 1][cx]
+                };
+
+                let erosion = (geologic_index + self.scan.depth) % EROSION_MOD;
+                self.erosion[cy].push(erosion);
+            }
+        }
+    }
+
+    fn region(&mut self, x: usize, y: usize) -> Region {
+        if y >= self.erosion.len() || x >= self.erosion[y].len() {
+            self.extend_to(x, y);
+        }
+
+        match self.erosion[y][x] % 3 {
+            0 => Region::Rocky,
+            1 => Region::Wet,
+            _ => Region::Narrow,
+        }
+    }
+}
+
+fn part1(scan: Scan) -> usize {
+    let mut cave = Cave::new(scan);
+    let (tx, ty) = scan.target;
+
+    (0..=ty)
+        .flat_map(|y| (0..=tx).map(move |x| (x, y)))
+        .map(|(x, y)| cave.region(x, y) as usize)
+        .sum()
+}
+
+fn part2(scan: Scan) -> u32 {
+    let mut cave = Cave::new(scan);
+    let start = (0, 0, Tool::Torch);
+    let goal = (scan.target.0, scan.target.1, Tool::Torch);
+
+    let mut best = HashMap::from([(start, 0)]);
+    let mut queue = BinaryHeap::from([Reverse((0, start))]);
+
+    while let Some(Reverse((time, state))) = queue.pop() {
+        if state == goal {
+            return time;
+        }
+        if best.get(&state).is_some_and(|&t| t < time) {
+            continue;
+        }
+
+        let (x, y, tool) = state;
+        let region = cave.region(x, y);
+        let mut next = Vec::with_capacity(6);
+
+        for other in [Tool::Neither, Tool::Torch, Tool::ClimbingGear] {
+            if other != tool && other.usable_in(region) {
+                next.push(((x, y, other), time + SWITCH_TIME));
+            }
+        }
+
+        let neighbours = [
+            x.checked_sub(1).map(|nx| (nx, y)),
+            y.checked_sub(1).map(|ny| (x, ny)),
+            Some((x + 1, y)),
+            Some((x, y + 1)),
+        ];
+        for (nx, ny) in neighbours.into_iter().flatten() {
+            if tool.usable_in(cave.region(nx, ny)) {
+                next.push(((nx, ny, tool), time + 1));
+            }
+        }
+
+        for (next_state, next_time) in next {
+            let entry = best.entry(next_state).or_insert(u32::MAX);
+            if next_time < *entry {
+                *entry = next_time;
+                queue.push(Reverse((next_time, next_state)));
+            }
+        }
+    }
+
+    unreachable!("the cave is unbounded, so the target is always reachable")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_lib::Example;
+
+    #[test]
+    fn part1_test() {
+        let data = aoc_lib::input(DAY.day)
+            .example(Example::Part1, 1)
+            .open()
+            .unwrap();
+
+        let parsed = parse(&data).unwrap();
+        let expected = 114;
+        let actual = part1(parsed);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn part2_test() {
+        let data = aoc_lib::input(DAY.day)
+            .example(Example::Part1, 1)
+            .open()
+            .unwrap();
+
+        let parsed = parse(&data).unwrap();
+        let expected = 45;
+        let actual = part2(parsed);
+
+        assert_eq!(expected, actual);
+    }
+}
@@ -0,0 +1,269 @@
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use aoc_lib::{Bench, BenchResult, Day, NoError, ParseResult, UserError};
+use chumsky::{primitive::just, text::digits, Parser as _};
+use color_eyre::{eyre::eyre, Report, Result};
+
+use crate::Parser;
+
+pub const DAY: Day = Day {
+    day: 23,
+    name: "Experimental Emergency Teleportation",
+    part_1: run_part1,
+    part_2: Some(run_part2),
+    other: &[("Parse", run_parse)],
+};
+
+fn run_part1(input: &str, b: Bench) -> BenchResult {
+    let data = parse(input).map_err(UserError)?;
+    b.bench(|| Ok::<_, NoError>(part1(&data)))
+}
+
+fn run_part2(input: &str, b: Bench) -> BenchResult {
+    let data = parse(input).map_err(UserError)?;
+    b.bench(|| Ok::<_, NoError>(part2(&data)))
+}
+
+fn run_parse(input: &str, b: Bench) -> BenchResult {
+    b.bench(|| {
+        let data = parse(input).map_err(UserError)?;
+        Ok::<_, Report>(ParseResult(data))
+    })
+}
+
+type Point = [i64; 3];
+
+fn distance(a: Point, b: Point) -> i64 {
+    (a[0] - b[0]).abs() + (a[1] - b[1]).abs() + (a[2] - b[2]).abs()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Nanobot {
+    pos: Point,
+    radius: i64,
+}
+
+fn signed<'a>() -> impl Parser<'a, i64> + Copy {
+    just('-')
+        .or_not()
+        .then(digits(10))
+        .to_slice()
+        .from_str::<i64>()
+        .unwrapped()
+        .padded()
+}
+
+impl Nanobot {
+    fn parser<'a>() -> impl Parser<'a, Nanobot> {
+        let pos = signed()
+            .then_ignore(just(','))
+            .then(signed())
+            .then_ignore(just(','))
+            .then(signed())
+            .delimited_by(just('<'), just('>'))
+            .map(|((x, y), z)| [x, y, z]);
+
+        just("pos=")
+            .ignore_then(pos)
+            .then_ignore(just(", r="))
+            .then(digits(10).to_slice().from_str::<i64>().unwrapped())
+            .map(|(pos, radius)| Nanobot { pos, radius })
+    }
+
+    fn parse(line: &str) -> Result<Nanobot> {
+        let res = Nanobot::parser()
+            .parse(line)
+            .into_result()
+            .map_err(|e| eyre!("Failed to parse: {e:?}"))?;
+
+        Ok(res)
+    }
+
+    fn in_range(&self, point: Point) -> bool {
+        distance(self.pos, point) <= self.radius
+    }
+}
+
+fn parse(input: &str) -> Result<Vec<Nanobot>> {
+    let bots: Vec<_> = input
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(Nanobot::parse)
+        .collect::<Result<_>>()?;
+
+    if bots.is_empty() {
+        return Err(eyre!("no nanobots in input"));
+    }
+
+    Ok(bots)
+}
+
+/// An axis-aligned cube of integer points, `size` along each edge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct Cube {
+    min: Point,
+    size: i64,
+}
+
+impl Cube {
+    /// Manhattan distance from `point` to the nearest point in the cube.
+    fn distance_to(&self, point: Point) -> i64 {
+        (0..3)
+            .map(|axis| {
+                let lo = self.min[axis];
+                let hi = lo + self.size - 1;
+                (lo - point[axis]).max(0) + (point[axis] - hi).max(0)
+            })
+            .sum()
+    }
+
+    fn bots_in_range(&self, bots: &[Nanobot]) -> usize {
+        bots.iter()
+            .filter(|b| self.distance_to(b.pos) <= b.radius)
+            .count()
+    }
+
+    fn split(&self) -> impl Iterator<Item = Cube> + '_ {
+        let half = self.size / 2;
+        (0..8).map(move |i| {
+            let offset = |bit: i64| if i & bit != 0 { half } else { 0 };
+            Cube {
+                min: [
+                    self.min[0] + offset(1),
+                    self.min[1] + offset(2),
+                    self.min[2] + offset(4),
+                ],
+                size: half,
+            }
+        })
+    }
+}
+
+/// A cube waiting to be searched. The ordering puts the most promising cube first: the one that
+/// could be in range of the most bots, then the one closest to the origin, then the smallest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct Candidate {
+    bots: usize,
+    distance: Reverse<i64>,
+    size: Reverse<i64>,
+    cube: Cube,
+}
+
+impl Candidate {
+    fn new(cube: Cube, bots: &[Nanobot]) -> Self {
+        Self {
+            bots: cube.bots_in_range(bots),
+            distance: Reverse(cube.distance_to([0; 3])),
+            size: Reverse(cube.size),
+            cube,
+        }
+    }
+}
+
+fn part1(bots: &[Nanobot]) -> usize {
+    let Some(strongest) = bots.iter().max_by_key(|b| b.radius) else {
+        return 0;
+    };
+
+    bots.iter().filter(|b| strongest.in_range(b.pos)).count()
+}
+
+/// Searches an octree over the space covered by the bots' ranges. A cube's bot count is an upper
+/// bound for every point inside it, and its distance a lower bound, so the first single point
+/// taken off the queue is the answer.
+fn part2(bots: &[Nanobot]) -> i64 {
+    let (min, max) = bots.iter().fold(([0; 3], [0; 3]), |(mut min, mut max), b| {
+        for axis in 0..3 {
+            min[axis] = min[axis].min(b.pos[axis] - b.radius);
+            max[axis] = max[axis].max(b.pos[axis] + b.radius);
+        }
+        (min, max)
+    });
+
+    let extent = (0..3).map(|axis| max[axis] - min[axis] + 1).max().unwrap();
+    let mut size = 1;
+    while size < extent {
+        size *= 2;
+    }
+
+    let mut queue = BinaryHeap::from([Candidate::new(Cube { min, size }, bots)]);
+
+    while let Some(candidate) = queue.pop() {
+        if candidate.cube.size == 1 {
+            return candidate.distance.0;
+        }
+
+        queue.extend(candidate.cube.split().map(|c| Candidate::new(c, bots)));
+    }
+
+    unreachable!("the starting cube always contains at least one point")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_lib::Example;
+
+    use crate::days::rng::Rng;
+
+    #[test]
+    fn part1_test() {
+        let data = aoc_lib::input(DAY.day)
+            .example(Example::Part1, 1)
+            .open()
+            .unwrap();
+
+        let parsed = parse(&data).unwrap();
+        let expected = 7;
+        let actual = part1(&parsed);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn part2_test() {
+        let data = aoc_lib::input(DAY.day)
+            .example(Example::Part2, 1)
+            .open()
+            .unwrap();
+
+        let parsed = parse(&data).unwrap();
+        let expected = 36;
+        let actual = part2(&parsed);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn part2_brute_force_test() {
+        // Small deterministic pseudo-random swarms, checked against every point in range.
+        let mut rng = Rng::new();
+        let mut next = |modulus: u64| rng.below(modulus) as i64;
+
+        for _ in 0..20 {
+            let bots: Vec<_> = (0..8)
+                .map(|_| Nanobot {
+                    pos: [next(21) - 10, next(21) - 10, next(21) - 10],
+                    radius: next(6) + 1,
+                })
+                .collect();
+
+            let mut best = (0, 0);
+            for x in -16..=16 {
+                for y in -16..=16 {
+                    for z in -16..=16 {
+                        let point = [x, y, z];
+                        let count = bots.iter().filter(|b| b.in_range(point)).count();
+                        let dist = distance(point, [0; 3]);
+                        if count > best.0 || (count == best.0 && dist < best.1) {
+                            best = (count, dist);
+                        }
+                    }
+                }
+            }
+
+            assert_eq!(best.1, part2(&bots), "{bots:?}");
+        }
+    }
+}
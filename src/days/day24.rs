@@ -0,0 +1,411 @@
+use std::cmp::Reverse;
+
+use aoc_lib::{Bench, BenchResult, Day, ParseResult, UserError};
+use chumsky::{
+    primitive::{any, choice, just},
+    text::digits,
+    IterParser, Parser as _,
+};
+use color_eyre::{eyre::eyre, Report, Result};
+
+use crate::Parser;
+
+pub const DAY: Day = Day {
+    day: 24,
+    name: "Immune System Simulator 20XX",
+    part_1: run_part1,
+    part_2: Some(run_part2),
+    other: &[("Parse", run_parse)],
+};
+
+fn run_part1(input: &str, b: Bench) -> BenchResult {
+    let data = parse(input).map_err(UserError)?;
+    b.bench(|| part1(&data))
+}
+
+fn run_part2(input: &str, b: Bench) -> BenchResult {
+    let data = parse(input).map_err(UserError)?;
+    b.bench(|| part2(&data).map(|(_, units)| units))
+}
+
+fn run_parse(input: &str, b: Bench) -> BenchResult {
+    b.bench(|| {
+        let data = parse(input).map_err(UserError)?;
+        Ok::<_, Report>(ParseResult(data))
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Army {
+    ImmuneSystem,
+    Infection,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Modifier<'a> {
+    Weak(Vec<&'a str>),
+    Immune(Vec<&'a str>),
+}
+
+/// A group as written in the input, before the damage types are turned into bitmasks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RawGroup<'a> {
+    units: u32,
+    hit_points: u32,
+    modifiers: Vec<Modifier<'a>>,
+    damage: u32,
+    damage_type: &'a str,
+    initiative: u32,
+}
+
+fn number<'a>() -> impl Parser<'a, u32> + Copy {
+    digits(10).to_slice().from_str::<u32>().unwrapped()
+}
+
+fn word<'a>() -> impl Parser<'a, &'a str> + Copy {
+    any()
+        .filter(char::is_ascii_lowercase)
+        .repeated()
+        .at_least(1)
+        .to_slice()
+}
+
+impl<'a> RawGroup<'a> {
+    fn parser() -> impl Parser<'a, RawGroup<'a>> {
+        let types = word().separated_by(just(", ")).at_least(1).collect();
+        let modifier = choice((
+            just("weak to ").ignore_then(types).map(Modifier::Weak),
+            just("immune to ").ignore_then(types).map(Modifier::Immune),
+        ));
+        let modifiers = modifier
+            .separated_by(just("; "))
+            .at_least(1)
+            .collect()
+            .delimited_by(just(" ("), just(")"))
+            .or_not()
+            .map(Option::unwrap_or_default);
+
+        number()
+            .then_ignore(just(" units each with "))
+            .then(number())
+            .then_ignore(just(" hit points"))
+            .then(modifiers)
+            .then_ignore(just(" with an attack that does "))
+            .then(number())
+            .then_ignore(just(' '))
+            .then(word())
+            .then_ignore(just(" damage at initiative "))
+            .then(number())
+            .map(
+                |(((((units, hit_points), modifiers), damage), damage_type), initiative)| {
+                    RawGroup {
+                        units,
+                        hit_points,
+                        modifiers,
+                        damage,
+                        damage_type,
+                        initiative,
+                    }
+                },
+            )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Group {
+    army: Army,
+    units: u32,
+    hit_points: u32,
+    damage: u32,
+    /// Damage types are stored as bit indices, so weaknesses and immunities can be bitmasks.
+    damage_type: u8,
+    weaknesses: u32,
+    immunities: u32,
+    initiative: u32,
+}
+
+impl Group {
+    fn effective_power(&self) -> u64 {
+        self.units as u64 * self.damage as u64
+    }
+
+    /// Damage this group would deal to `target`, before accounting for whole units.
+    fn damage_to(&self, target: &Group) -> u64 {
+        let bit = 1 << self.damage_type;
+        if target.immunities & bit != 0 {
+            0
+        } else if target.weaknesses & bit != 0 {
+            self.effective_power() * 2
+        } else {
+            self.effective_power()
+        }
+    }
+}
+
+fn parse(input: &str) -> Result<Vec<Group>> {
+    let mut damage_types: Vec<&str> = Vec::new();
+    let mut type_bit = |name| -> Result<u8> {
+        let idx = match damage_types.iter().position(|&t| t == name) {
+            Some(idx) => idx,
+            None => {
+                damage_types.push(name);
+                damage_types.len() - 1
+            }
+        };
+
+        if idx >= u32::BITS as usize {
+            return Err(eyre!("too many damage types"));
+        }
+        Ok(idx as u8)
+    };
+
+    let mut groups = Vec::new();
+    let mut army = None;
+
+    for line in input.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        match line {
+            "Immune System:" => army = Some(Army::ImmuneSystem),
+            "Infection:" => army = Some(Army::Infection),
+            _ => {
+                let army = army.ok_or_else(|| eyre!("group listed before any army"))?;
+                let raw = RawGroup::parser()
+                    .parse(line)
+                    .into_result()
+                    .map_err(|e| eyre!("Failed to parse `{line}`: {e:?}"))?;
+
+                let (mut weaknesses, mut immunities) = (0, 0);
+                for modifier in &raw.modifiers {
+                    let (mask, types) = match modifier {
+                        Modifier::Weak(types) => (&mut weaknesses, types),
+                        Modifier::Immune(types) => (&mut immunities, types),
+                    };
+                    for &name in types {
+                        *mask |= 1 << type_bit(name)?;
+                    }
+                }
+
+                groups.push(Group {
+                    army,
+                    units: raw.units,
+                    hit_points: raw.hit_points,
+                    damage: raw.damage,
+                    damage_type: type_bit(raw.damage_type)?,
+                    weaknesses,
+                    immunities,
+                    initiative: raw.initiative,
+                });
+            }
+        }
+    }
+
+    for army in [Army::ImmuneSystem, Army::Infection] {
+        if !groups.iter().any(|g| g.army == army && g.units > 0) {
+            return Err(eyre!("no groups in the {army:?} army"));
+        }
+    }
+
+    Ok(groups)
+}
+
+/// Picks a target for each group, returning the index of the group each one will attack.
+fn select_targets(groups: &[Group]) -> Vec<Option<usize>> {
+    let mut order: Vec<_> = (0..groups.len()).collect();
+    order.sort_by_key(|&i| {
+        let g = &groups[i];
+        Reverse((g.effective_power(), g.initiative))
+    });
+
+    let mut targets = vec![None; groups.len()];
+    let mut targeted = vec![false; groups.len()];
+
+    for attacker_idx in order {
+        let attacker = &groups[attacker_idx];
+        let target = groups
+            .iter()
+            .enumerate()
+            .filter(|&(i, g)| g.army != attacker.army && !targeted[i])
+            .map(|(i, g)| (attacker.damage_to(g), g.effective_power(), g.initiative, i))
+            .filter(|&(damage, ..)| damage > 0)
+            .max();
+
+        if let Some((.., target_idx)) = target {
+            targets[attacker_idx] = Some(target_idx);
+            targeted[target_idx] = true;
+        }
+    }
+
+    targets
+}
+
+/// Runs one round of the fight. Returns the number of units killed.
+fn round(groups: &mut Vec<Group>) -> u32 {
+    let targets = select_targets(groups);
+
+    let mut order: Vec<_> = (0..groups.len()).collect();
+    order.sort_by_key(|&i| Reverse(groups[i].initiative));
+
+    let mut killed = 0;
+    for attacker_idx in order {
+        let Some(target_idx) = targets[attacker_idx] else {
+            continue;
+        };
+        if groups[attacker_idx].units == 0 {
+            continue;
+        }
+
+        let damage = groups[attacker_idx].damage_to(&groups[target_idx]);
+        let target = &mut groups[target_idx];
+        let dead = (damage / target.hit_points as u64).min(target.units as u64) as u32;
+        target.units -= dead;
+        killed += dead;
+    }
+
+    groups.retain(|g| g.units > 0);
+    killed
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+    Victory {
+        winner: Army,
+        units: u32,
+    },
+    /// Neither army can kill any more units, so the fight would never end.
+    Stalemate,
+}
+
+fn fight(groups: &[Group], boost: u32) -> Outcome {
+    let mut groups: Vec<_> = groups
+        .iter()
+        .map(|&g| match g.army {
+            Army::ImmuneSystem => Group {
+                damage: g.damage.saturating_add(boost),
+                ..g
+            },
+            Army::Infection => g,
+        })
+        .filter(|g| g.units > 0)
+        .collect();
+
+    loop {
+        let first_army = groups[0].army;
+        if groups.iter().all(|g| g.army == first_army) {
+            return Outcome::Victory {
+                winner: first_army,
+                units: groups.iter().map(|g| g.units).sum(),
+            };
+        }
+
+        if round(&mut groups) == 0 {
+            return Outcome::Stalemate;
+        }
+    }
+}
+
+fn part1(groups: &[Group]) -> Result<u32> {
+    match fight(groups, 0) {
+        Outcome::Victory { units, .. } => Ok(units),
+        Outcome::Stalemate => Err(eyre!("the fight ends in a stalemate")),
+    }
+}
+
+fn part2(groups: &[Group]) -> Result<(u32, u32)> {
+    // Once the boost is big enough that any immune system attack wipes out any infection group,
+    // more boost can't help, so check there's any hope before searching.
+    let max_boost = groups
+        .iter()
+        .filter(|g| g.army == Army::Infection)
+        .map(|g| g.units.saturating_mul(g.hit_points))
+        .max()
+        .unwrap_or(0);
+
+    let immune_wins = |boost| match fight(groups, boost) {
+        Outcome::Victory {
+            winner: Army::ImmuneSystem,
+            units,
+        } => Some((boost, units)),
+        _ => None,
+    };
+
+    if immune_wins(max_boost).is_none() {
+        return Err(eyre!(
+            "the immune system can't win even with a boost of {max_boost}"
+        ));
+    }
+
+    (1..=max_boost)
+        .find_map(immune_wins)
+        .ok_or_else(|| eyre!("no boost lets the immune system win"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_lib::Example;
+
+    #[test]
+    fn parse_test() {
+        let line = "989 units each with 1274 hit points (immune to fire; weak to bludgeoning, slashing) with an attack that does 25 slashing damage at initiative 3";
+        let expected = RawGroup {
+            units: 989,
+            hit_points: 1274,
+            modifiers: vec![
+                Modifier::Immune(vec!["fire"]),
+                Modifier::Weak(vec!["bludgeoning", "slashing"]),
+            ],
+            damage: 25,
+            damage_type: "slashing",
+            initiative: 3,
+        };
+        let actual = RawGroup::parser().parse(line).into_result().unwrap();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn part1_test() {
+        let data = aoc_lib::input(DAY.day)
+            .example(Example::Part1, 1)
+            .open()
+            .unwrap();
+
+        let parsed = parse(&data).unwrap();
+        let expected = 5216;
+        let actual = part1(&parsed).unwrap();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn part2_test() {
+        let data = aoc_lib::input(DAY.day)
+            .example(Example::Part1, 1)
+            .open()
+            .unwrap();
+
+        let parsed = parse(&data).unwrap();
+        let expected = Outcome::Victory {
+            winner: Army::ImmuneSystem,
+            units: 51,
+        };
+        assert_eq!(expected, fight(&parsed, 1570));
+
+        assert_eq!((1570, 51), part2(&parsed).unwrap());
+    }
+
+    #[test]
+    fn stalemate_test() {
+        let input = "\
+Immune System:
+10 units each with 100 hit points (immune to cold) with an attack that does 10 fire damage at initiative 2
+
+Infection:
+10 units each with 100 hit points (immune to fire) with an attack that does 10 cold damage at initiative 1
+";
+        let parsed = parse(input).unwrap();
+
+        assert_eq!(Outcome::Stalemate, fight(&parsed, 0));
+        assert!(part1(&parsed).is_err());
+        assert!(part2(&parsed).is_err());
+    }
+}
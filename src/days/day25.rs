@@ -0,0 +1,124 @@
+use aoc_lib::{Bench, BenchResult, Day, NoError, ParseResult, UserError};
+use color_eyre::{eyre::eyre, Report, Result};
+
+pub const DAY: Day = Day {
+    day: 25,
+    name: "Four-Dimensional Adventure",
+    part_1: run_part1,
+    part_2: None,
+    other: &[("Parse", run_parse)],
+};
+
+fn run_part1(input: &str, b: Bench) -> BenchResult {
+    let data = parse(input).map_err(UserError)?;
+    b.bench(|| Ok::<_, NoError>(part1(&data)))
+}
+
+fn run_parse(input: &str, b: Bench) -> BenchResult {
+    b.bench(|| {
+        let data = parse(input).map_err(UserError)?;
+        Ok::<_, Report>(ParseResult(data))
+    })
+}
+
+const MAX_LINK_DISTANCE: i32 = 3;
+
+type Point = [i32; 4];
+
+fn distance(a: &Point, b: &Point) -> i32 {
+    a.iter().zip(b).map(|(a, b)| (a - b).abs()).sum()
+}
+
+fn parse(input: &str) -> Result<Vec<Point>> {
+    input
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(|line| {
+            let coords = line
+                .split(',')
+                .map(|c| c.trim().parse())
+                .collect::<Result<Vec<i32>, _>>()?;
+
+            coords
+                .try_into()
+                .map_err(|_| eyre!("expected 4 coordinates in `{line}`"))
+        })
+        .collect()
+}
+
+/// Disjoint sets over indices, with path halving and union by size.
+struct UnionFind {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+    sets: usize,
+}
+
+impl UnionFind {
+    fn new(len: usize) -> Self {
+        Self {
+            parent: (0..len).collect(),
+            size: vec![1; len],
+            sets: len,
+        }
+    }
+
+    fn find(&mut self, mut i: usize) -> usize {
+        while self.parent[i] != i {
+            self.parent[i] = self.parent[self.parent[i]];
+            i = self.parent[i];
+        }
+        i
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return;
+        }
+        if self.size[a] < self.size[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+        self.sets -= 1;
+    }
+}
+
+fn part1(points: &[Point]) -> usize {
+    let mut sets = UnionFind::new(points.len());
+
+    for (i, a) in points.iter().enumerate() {
+        for (j, b) in points.iter().enumerate().skip(i + 1) {
+            if distance(a, b) <= MAX_LINK_DISTANCE {
+                sets.union(i, j);
+            }
+        }
+    }
+
+    sets.sets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_lib::Example;
+
+    #[test]
+    fn part1_test() {
+        let tests = [(1, 2), (2, 4), (3, 3), (4, 8)];
+
+        for (example, expected) in tests {
+            let data = aoc_lib::input(DAY.day)
+                .example(Example::Part1, example)
+                .open()
+                .unwrap();
+
+            let parsed = parse(&data).unwrap();
+            let actual = part1(&parsed);
+
+            assert_eq!(expected, actual, "example {example}");
+        }
+    }
+}
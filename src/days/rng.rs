@@ -0,0 +1,19 @@
+/// A small seeded generator, so the randomised tests check the same cases on every run.
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new() -> Self {
+        Self { state: 0x2018 }
+    }
+
+    /// Returns a number in `0..modulus`.
+    pub fn below(&mut self, modulus: u64) -> u64 {
+        self.state = self
+            .state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.state >> 33) % modulus
+    }
+}